@@ -232,6 +232,10 @@ def results_from_file(path: str) -> list[BenchResult]:
     )
 
     if isinstance(test, dict):
+        # results files with metadata keep the results in their own object
+        if "metadata" in test and "results" in test:
+            test = test["results"]
+
         return [to_bench_result(name, pre) for name, pre in test.items()]
     else:
         raise Exception("Invalid JSON format")
@@ -0,0 +1,129 @@
+use std::{
+    collections::{BTreeSet, HashMap},
+    path::{Path, PathBuf},
+};
+
+use anyhow::Context;
+
+use super::{check_schema_version, format_time, tree, BenchResult, StoredResults, TimeUnit};
+
+/// Results that the current results are compared against.
+#[derive(Debug, Clone)]
+pub enum Baseline {
+    /// Results file at the given path.
+    File(PathBuf),
+
+    /// The current results file as it was committed at the given git revision.
+    Revision(String),
+}
+
+/// Prints how the mean run time of each benchmarked file changed between the baseline and the
+/// current results. Files that were only benchmarked in one of them are listed as such.
+pub fn compare_results(
+    baseline: &Baseline,
+    current: &Path,
+    time_unit: TimeUnit,
+) -> anyhow::Result<()> {
+    let contents = std::fs::read_to_string(current)
+        .with_context(|| format!("Failed reading '{}'.", current.display()))?;
+    let current_results = read_results(&contents, current)?;
+
+    let baseline_results = match baseline {
+        Baseline::File(path) => {
+            let contents = std::fs::read_to_string(path)
+                .with_context(|| format!("Failed reading '{}'.", path.display()))?;
+            read_results(&contents, path)?
+        }
+        Baseline::Revision(rev) => {
+            let contents = committed_results(current, rev)?;
+            read_results(
+                &contents,
+                &PathBuf::from(format!("{rev}:{}", current.display())),
+            )?
+        }
+    };
+
+    let files: BTreeSet<&String> = baseline_results
+        .keys()
+        .chain(current_results.keys())
+        .collect();
+
+    let format = |result: &BenchResult| match result {
+        BenchResult::Success { .. } => format_time(result.mean_time(), time_unit),
+        BenchResult::Failed { .. } => String::from("failed"),
+    };
+
+    for file in files {
+        match (baseline_results.get(file), current_results.get(file)) {
+            (Some(old), Some(new)) => {
+                print!("{file}: {} -> {}", format(old), format(new));
+
+                if let (BenchResult::Success { .. }, BenchResult::Success { .. }) = (old, new) {
+                    let change = (new.mean_time() / old.mean_time() - 1.0) * 100.0;
+
+                    if change.is_finite() {
+                        print!(" ({change:+.1}%)");
+                    }
+                }
+
+                println!();
+            }
+            (Some(_), None) => println!("{file}: only in baseline"),
+            (None, Some(_)) => println!("{file}: only in current results"),
+            (None, None) => {}
+        }
+    }
+
+    Ok(())
+}
+
+/// Reads the results file stored at `path` as it was committed at the revision, using the git
+/// repository that contains it.
+fn committed_results(path: &Path, rev: &str) -> anyhow::Result<String> {
+    let dir = match path.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir,
+        _ => Path::new("."),
+    };
+    let name = path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .with_context(|| format!("Invalid results path '{}'.", path.display()))?;
+
+    tree::git(
+        dir,
+        &[
+            "rev-parse",
+            "--verify",
+            "--quiet",
+            &format!("{rev}^{{commit}}"),
+        ],
+    )
+    .with_context(|| format!("Unknown git revision '{rev}'."))?;
+
+    // './' makes git resolve the path relative to `dir` instead of the root of the repository
+    let object = format!("{rev}:./{name}");
+
+    if tree::git(dir, &["cat-file", "-e", &object]).is_err() {
+        anyhow::bail!(
+            "Results file '{}' did not exist at revision '{rev}'.",
+            path.display()
+        );
+    }
+
+    tree::git(dir, &["show", &object])
+}
+
+/// Parses the contents of a results file, which is named by `path` in errors.
+fn read_results(contents: &str, path: &Path) -> anyhow::Result<HashMap<String, BenchResult>> {
+    let stored: serde_json::Value = serde_json::from_str(contents)
+        .with_context(|| format!("Failed deserializing results file '{}'.", path.display()))?;
+
+    check_schema_version(&stored, path)?;
+
+    let stored: StoredResults = serde_json::from_value(stored)
+        .with_context(|| format!("Failed deserializing results file '{}'.", path.display()))?;
+
+    Ok(match stored {
+        StoredResults::WithMetadata { results } | StoredResults::Plain(results) => results,
+    })
+}
@@ -0,0 +1,95 @@
+/// Whether the name matches the glob pattern. `*` matches any number of characters, `?` matches
+/// a single character and `[...]` matches one of the listed characters or ranges, e.g. `[a-z]`.
+/// The set is negated if it starts with `!`. All other characters match themselves.
+pub(super) fn matches(pattern: &str, name: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let name: Vec<char> = name.chars().collect();
+
+    matches_from(&pattern, &name)
+}
+
+fn matches_from(pattern: &[char], name: &[char]) -> bool {
+    match pattern.first() {
+        None => name.is_empty(),
+        Some('*') => (0..=name.len()).any(|skip| matches_from(&pattern[1..], &name[skip..])),
+        Some(_) if name.is_empty() => false,
+        Some('?') => matches_from(&pattern[1..], &name[1..]),
+        Some('[') => match match_set(&pattern[1..], name[0]) {
+            Some((true, len)) => matches_from(&pattern[1 + len..], &name[1..]),
+            Some((false, _)) => false,
+            // unterminated set, '[' is matched literally
+            None => name[0] == '[' && matches_from(&pattern[1..], &name[1..]),
+        },
+        Some(c) => *c == name[0] && matches_from(&pattern[1..], &name[1..]),
+    }
+}
+
+/// Matches the character against the set at the start of `set`, which is the pattern after the
+/// opening `[`. Returns whether it matched and the length of the set including the closing `]`,
+/// `None` if the set is not terminated.
+fn match_set(set: &[char], c: char) -> Option<(bool, usize)> {
+    let negated = set.first() == Some(&'!');
+    let start = usize::from(negated);
+
+    // a ']' right at the start is part of the set
+    let end = start + 1 + set.get(start + 1..)?.iter().position(|&c| c == ']')?;
+    let items = &set[start..end];
+
+    let mut found = false;
+    let mut idx = 0;
+
+    while idx < items.len() {
+        if idx + 2 < items.len() && items[idx + 1] == '-' {
+            found |= (items[idx]..=items[idx + 2]).contains(&c);
+            idx += 3;
+        } else {
+            found |= items[idx] == c;
+            idx += 1;
+        }
+    }
+
+    Some((found != negated, end + 1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn star_matches_any_run_of_characters() {
+        assert!(matches("*.btor2", "rotor.btor2"));
+        assert!(matches("*.btor2", ".btor2"));
+        assert!(!matches("*.btor2", "rotor.btor"));
+        assert!(matches("**", ""));
+        assert!(matches("a**b", "ab"));
+        assert!(matches("a**b", "a-x-b"));
+        assert!(!matches("a**b", "a-x-c"));
+    }
+
+    #[test]
+    fn question_mark_matches_one_character() {
+        assert!(matches("rotor-?.btor2", "rotor-1.btor2"));
+        assert!(!matches("rotor-?.btor2", "rotor-.btor2"));
+        assert!(!matches("rotor-?.btor2", "rotor-12.btor2"));
+    }
+
+    #[test]
+    fn sets_match_listed_characters_and_ranges() {
+        assert!(matches("[ab]c", "bc"));
+        assert!(!matches("[ab]c", "cc"));
+        assert!(matches("x[0-9]", "x7"));
+        assert!(!matches("x[0-9]", "xa"));
+        assert!(matches("[!0-9]", "a"));
+        assert!(!matches("[!0-9]", "5"));
+        assert!(matches("[]]", "]"));
+        assert!(matches("[a", "[a"));
+    }
+
+    #[test]
+    fn literal_pattern_matches_only_itself() {
+        assert!(matches("rotor.btor2", "rotor.btor2"));
+        assert!(!matches("rotor.btor2", "other.btor2"));
+        assert!(!matches("rotor.btor2", "rotor.btor2.gz"));
+        assert!(!matches("rotor.btor2", ""));
+    }
+}
@@ -3,6 +3,8 @@ use std::{fs::OpenOptions, io::Read, path::Path, process::Command};
 use anyhow::Context;
 use serde::{Deserialize, Serialize};
 
+use super::{interrupt, BenchConfig, DEFAULT_SAMPLES, DEFAULT_WARMUP};
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Hyperfine {
     pub results: Vec<HyperfineResult>,
@@ -22,12 +24,31 @@ pub struct HyperfineResult {
     pub exit_codes: Vec<i32>,
 }
 
+/// Exit code of the `timeout` command if the command timed out.
+const TIMEOUT_EXIT_CODE: i32 = 124;
+
+impl Hyperfine {
+    /// Whether any of the benchmarked runs were stopped by the timeout.
+    pub fn timed_out(&self) -> bool {
+        self.results
+            .iter()
+            .flat_map(|result| &result.exit_codes)
+            .any(|code| *code == TIMEOUT_EXIT_CODE)
+    }
+
+    /// Mean run time of the benchmarked commands in seconds.
+    pub fn mean_time(&self) -> f64 {
+        self.results.iter().map(|result| result.mean).sum()
+    }
+}
+
+/// Benchmarks `btormc` on the file with the flags, timeout, thread limit and number of warmup and
+/// measured runs of the config. Only the measured runs are part of the results.
 pub fn run(
     path: impl AsRef<Path>,
     hyperfine_output: impl AsRef<Path>,
     hyperfine_json_path: impl AsRef<Path>,
-    btormc_flags: &Option<String>,
-    timeout: Option<u128>,
+    config: &BenchConfig,
 ) -> anyhow::Result<Hyperfine> {
     let json_path = hyperfine_json_path.as_ref();
     let mut json_out = OpenOptions::new()
@@ -37,29 +58,42 @@ pub fn run(
         .read(true)
         .open(json_path)?;
 
-    let btormc_flags = btormc_flags.as_deref().unwrap_or("-kmax 200");
+    let btormc_flags = config.btormc_flags.as_deref().unwrap_or("-kmax 200");
     let mut btormc_cmd = format!("btormc {} {}", path.as_ref().display(), btormc_flags);
 
-    if let Some(timeout) = timeout {
+    if let Some(timeout) = config.timeout {
         btormc_cmd = format!("timeout --foreground {}s {}", timeout, btormc_cmd);
     }
 
-    let _ = Command::new("hyperfine")
-        .args(["--warmup", "3"])
-        .args(["--runs", "5"])
-        .arg("--ignore-failure")
-        .arg("--export-json")
-        .arg(json_path)
-        .args([
-            "--output",
-            hyperfine_output
-                .as_ref()
-                .to_str()
-                .context("Invalid path for output from 'btormc'")?,
-        ])
-        .arg(&btormc_cmd)
-        .spawn()?
-        .wait()?;
+    let mut hyperfine = Command::new("hyperfine");
+
+    // btormc has no option for its thread count, the limit is inherited through hyperfine
+    if let Some(threads) = config.btormc_threads {
+        hyperfine.env("OMP_NUM_THREADS", threads.to_string());
+    }
+
+    let _ = interrupt::run(
+        hyperfine
+            .args([
+                "--warmup",
+                &config.warmup.unwrap_or(DEFAULT_WARMUP).to_string(),
+            ])
+            .args([
+                "--runs",
+                &config.samples.unwrap_or(DEFAULT_SAMPLES).to_string(),
+            ])
+            .arg("--ignore-failure")
+            .arg("--export-json")
+            .arg(json_path)
+            .args([
+                "--output",
+                hyperfine_output
+                    .as_ref()
+                    .to_str()
+                    .context("Invalid path for output from 'btormc'")?,
+            ])
+            .arg(&btormc_cmd),
+    )?;
 
     let hyperfine: Hyperfine = serde_json::from_reader(&json_out).map_err(|_| {
         let mut output = String::new();
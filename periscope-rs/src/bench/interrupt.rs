@@ -0,0 +1,106 @@
+use std::{
+    process::{Child, Command, ExitStatus},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Once,
+    },
+    time::Duration,
+};
+
+/// Set once Ctrl-C was pressed.
+static INTERRUPTED: AtomicBool = AtomicBool::new(false);
+
+/// How often running child processes are checked for whether they finished.
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Installs the handler for Ctrl-C (SIGINT). Instead of terminating periscope, the handler only
+/// records the interruption, so that the benchmarks can be stopped and the results written.
+pub(super) fn install_handler() {
+    static INSTALL: Once = Once::new();
+
+    INSTALL.call_once(sys::install_handler);
+}
+
+/// Whether Ctrl-C was pressed since the handler was installed.
+pub(super) fn requested() -> bool {
+    INTERRUPTED.load(Ordering::Relaxed)
+}
+
+/// Runs the command to completion. The command runs in its own process group, so that on Ctrl-C
+/// it and all of its children, e.g. `btormc` started by `hyperfine`, can be stopped.
+pub(super) fn run(command: &mut Command) -> anyhow::Result<ExitStatus> {
+    sys::own_process_group(command);
+
+    let mut child = command.spawn()?;
+
+    loop {
+        if let Some(status) = child.try_wait()? {
+            return Ok(status);
+        }
+
+        if requested() {
+            stop(&mut child);
+            anyhow::bail!("Interrupted by Ctrl-C.");
+        }
+
+        std::thread::sleep(POLL_INTERVAL);
+    }
+}
+
+fn stop(child: &mut Child) {
+    sys::terminate_group(child.id());
+
+    let _ = child.kill();
+    let _ = child.wait();
+}
+
+#[cfg(unix)]
+mod sys {
+    use std::{os::unix::process::CommandExt, process::Command, sync::atomic::Ordering};
+
+    use super::INTERRUPTED;
+
+    const SIGINT: i32 = 2;
+    const SIGTERM: i32 = 15;
+
+    extern "C" {
+        fn signal(signum: i32, handler: extern "C" fn(i32)) -> usize;
+        fn kill(pid: i32, sig: i32) -> i32;
+    }
+
+    extern "C" fn on_interrupt(_: i32) {
+        INTERRUPTED.store(true, Ordering::Relaxed);
+    }
+
+    pub(super) fn install_handler() {
+        // SAFETY: the handler only stores into an atomic, which is async-signal-safe.
+        unsafe {
+            signal(SIGINT, on_interrupt);
+        }
+    }
+
+    pub(super) fn own_process_group(command: &mut Command) {
+        command.process_group(0);
+    }
+
+    pub(super) fn terminate_group(pid: u32) {
+        if let Ok(pid) = i32::try_from(pid) {
+            // SAFETY: sending a signal has no memory safety requirements. The process group was
+            // created for the child, so no other processes are affected.
+            unsafe {
+                kill(-pid, SIGTERM);
+            }
+        }
+    }
+}
+
+#[cfg(not(unix))]
+mod sys {
+    use std::process::Command;
+
+    pub(super) fn install_handler() {}
+
+    pub(super) fn own_process_group(_: &mut Command) {}
+
+    pub(super) fn terminate_group(_: u32) {}
+}
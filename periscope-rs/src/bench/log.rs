@@ -0,0 +1,65 @@
+use std::{
+    fs::File,
+    io::{LineWriter, Write},
+    path::Path,
+    time::SystemTime,
+};
+
+use anyhow::Context;
+use serde_json::json;
+
+use super::{metadata::iso_timestamp, BenchEvent};
+
+/// Log of the lifecycle events of a benchmark sweep with one JSON object per line. Each line is
+/// written as soon as the event happens, so the log can be followed while benchmarking.
+pub struct EventLog {
+    file: LineWriter<File>,
+}
+
+impl EventLog {
+    /// Creates the log file at `path`, truncating an existing one.
+    pub fn create(path: &Path) -> anyhow::Result<Self> {
+        let file =
+            File::create(path).with_context(|| format!("Failed creating '{}'.", path.display()))?;
+
+        Ok(Self {
+            file: LineWriter::new(file),
+        })
+    }
+
+    /// Writes the event together with the current time. Progress estimates are not lifecycle
+    /// events and are not logged.
+    pub fn write(&mut self, event: &BenchEvent) -> anyhow::Result<()> {
+        let mut entry = match event {
+            BenchEvent::RunStarted { name } => json!({ "event": "run_started", "run": name }),
+            BenchEvent::Started { file } => json!({ "event": "started", "file": file }),
+            BenchEvent::Finished {
+                file,
+                wc_raw,
+                wc_btormc_dump,
+                props,
+                steps,
+                mean_time,
+                chars_per_second,
+            } => json!({
+                "event": "finished",
+                "file": file,
+                "wc_raw": wc_raw,
+                "wc_btormc_dump": wc_btormc_dump,
+                "props": props,
+                "steps": steps,
+                "mean_time": mean_time,
+                "chars_per_second": chars_per_second,
+            }),
+            BenchEvent::Failed { file, output } => {
+                json!({ "event": "failed", "file": file, "output": output })
+            }
+            BenchEvent::TimedOut { file } => json!({ "event": "timed_out", "file": file }),
+            BenchEvent::Progress { .. } => return Ok(()),
+        };
+
+        entry["timestamp"] = json!(iso_timestamp(SystemTime::now()));
+
+        writeln!(self.file, "{entry}").context("Failed writing the event log.")
+    }
+}
@@ -0,0 +1,101 @@
+use std::{
+    path::Path,
+    process::Command,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use serde::{Deserialize, Serialize};
+
+use super::{tree::SelfieTree, BenchConfig};
+
+/// Information about how the results were produced, stored next to the results.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(super) struct Metadata {
+    /// Version of periscope that produced the results.
+    pub periscope_version: String,
+
+    /// Time at which benchmarking started, in UTC and ISO 8601 format.
+    pub timestamp: String,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hostname: Option<String>,
+
+    /// Number of CPUs available to periscope.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cpus: Option<usize>,
+
+    /// Commit of the selfie repository that rotor was run from.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub selfie_commit: Option<String>,
+
+    /// Arguments that rotor was run with.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rotor_args: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub selfie_tree: Option<SelfieTree>,
+
+    /// Configuration of the benchmarks, including the options from the config file.
+    pub config: BenchConfig,
+}
+
+impl Metadata {
+    /// Collects the metadata about the current environment. The selfie commit is only resolved if
+    /// `selfie_dir` is provided and is a git repository.
+    pub fn collect(config: &BenchConfig, selfie_dir: Option<&Path>) -> Self {
+        Self {
+            periscope_version: env!("CARGO_PKG_VERSION").to_string(),
+            timestamp: iso_timestamp(SystemTime::now()),
+            hostname: command_output(Command::new("hostname")),
+            cpus: std::thread::available_parallelism()
+                .ok()
+                .map(|cpus| cpus.get()),
+            selfie_commit: selfie_dir.and_then(|dir| {
+                let mut git = Command::new("git");
+                git.args(["rev-parse", "HEAD"]).current_dir(dir);
+                command_output(git)
+            }),
+            rotor_args: None,
+            selfie_tree: None,
+            config: config.clone(),
+        }
+    }
+}
+
+/// Trimmed output of the command, `None` if it fails or prints nothing.
+fn command_output(mut command: Command) -> Option<String> {
+    let output = command.output().ok()?;
+    let stdout = String::from_utf8(output.stdout).ok()?;
+    let stdout = stdout.trim();
+
+    (output.status.success() && !stdout.is_empty()).then(|| stdout.to_string())
+}
+
+/// Formats the time as `YYYY-MM-DDTHH:MM:SSZ`.
+pub(super) fn iso_timestamp(time: SystemTime) -> String {
+    let secs = time
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_secs());
+
+    let days = (secs / 86_400) as i64;
+    let secs_of_day = secs % 86_400;
+
+    // civil date from days since 1970-01-01, see http://howardhinnant.github.io/date_algorithms.html
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let day_of_era = z.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+
+    format!(
+        "{year:04}-{month:02}-{day:02}T{:02}:{:02}:{:02}Z",
+        secs_of_day / 3600,
+        secs_of_day % 3600 / 60,
+        secs_of_day % 60
+    )
+}
@@ -1,20 +1,35 @@
 use std::{
-    collections::HashMap,
+    collections::{BTreeMap, HashMap},
     ffi::OsStr,
     fs::{DirEntry, File},
-    io::{StdoutLock, Write},
     path::{Path, PathBuf},
+    time::{Duration, Instant},
 };
 
 use anyhow::Context;
 use serde::{Deserialize, Serialize};
 
-use crate::btor;
+use crate::{
+    btor::{self, WitnessOutcome},
+    output,
+};
+
+pub use self::{
+    compare::{compare_results, Baseline},
+    log::EventLog,
+};
 
-use self::hyperfine::Hyperfine;
+use self::{hyperfine::Hyperfine, metadata::Metadata, progress::Progress};
 
+mod compare;
+mod glob;
 mod hyperfine;
+mod interrupt;
+mod log;
+mod metadata;
+mod progress;
 mod rotor;
+mod tree;
 mod wc;
 
 // What I need to do:
@@ -34,11 +49,132 @@ pub struct BenchConfig {
     pub timeout: Option<u128>,
     #[serde(rename = "btormc-flags")]
     pub btormc_flags: Option<String>,
+    /// Number of threads each `btormc` run may use, passed as `OMP_NUM_THREADS`.
+    #[serde(default, rename = "btormc-threads")]
+    pub btormc_threads: Option<u32>,
     pub files: Vec<String>,
-    pub runs: HashMap<String, String>,
+    pub runs: BTreeMap<String, String>,
+    /// Targets for `make` that are run in order instead of the default rotor targets.
+    #[serde(rename = "make-targets")]
+    pub make_targets: Option<Vec<String>>,
 
     #[serde(skip)]
     pub results_path: Option<PathBuf>,
+
+    #[serde(skip)]
+    pub recursive: bool,
+
+    #[serde(skip)]
+    pub sort_by: SortBy,
+
+    /// Stop benchmarking after the first benchmark that failed as given by `fail_on`.
+    #[serde(skip)]
+    pub fail_fast: bool,
+
+    #[serde(skip)]
+    pub fail_on: FailOn,
+
+    /// Record the state of the selfie git tree in the results metadata.
+    #[serde(skip)]
+    pub verify_tree: bool,
+
+    /// Only files whose name matches one of these globs are benchmarked, all if empty.
+    #[serde(skip)]
+    pub include_globs: Vec<String>,
+
+    /// Files whose name matches one of these globs are not benchmarked, even if they match
+    /// `include_globs`.
+    #[serde(skip)]
+    pub exclude_globs: Vec<String>,
+
+    /// Number of measured runs of `btormc` per file, [`DEFAULT_SAMPLES`] if not set.
+    #[serde(skip)]
+    pub samples: Option<u32>,
+
+    /// Number of runs of `btormc` per file before the measured runs, [`DEFAULT_WARMUP`] if not
+    /// set.
+    #[serde(skip)]
+    pub warmup: Option<u32>,
+}
+
+/// Number of measured runs of `btormc` per file if not configured otherwise.
+pub const DEFAULT_SAMPLES: u32 = 5;
+
+/// Number of unmeasured runs of `btormc` per file if not configured otherwise.
+pub const DEFAULT_WARMUP: u32 = 3;
+
+/// Which benchmarks count as failed for `fail_fast`.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum FailOn {
+    /// Benchmarks that did not produce a counterexample or timed out.
+    #[default]
+    Any,
+
+    /// Benchmarks that did not produce a counterexample, but did not time out.
+    Failure,
+
+    /// Benchmarks that timed out.
+    Timeout,
+}
+
+impl FailOn {
+    fn matches(self, event: &BenchEvent) -> bool {
+        matches!(
+            (self, event),
+            (FailOn::Any | FailOn::Failure, BenchEvent::Failed { .. })
+                | (FailOn::Any | FailOn::Timeout, BenchEvent::TimedOut { .. })
+        )
+    }
+}
+
+/// Order in which the benchmark results are written into the results file.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum SortBy {
+    /// Sort by file name.
+    #[default]
+    Name,
+
+    /// Sort by character count of the BTOR2 file, largest first.
+    Chars,
+
+    /// Sort by mean run time of `btormc`, slowest first.
+    Time,
+
+    /// Sort by characters of the BTOR2 file per second of run time of `btormc`, fastest first.
+    /// Failed benchmarks come last.
+    Speed,
+}
+
+impl BenchConfig {
+    /// Whether the file is selected by the include and exclude globs.
+    fn selects(&self, path: &Path) -> bool {
+        let name = file_name(path);
+
+        (self.include_globs.is_empty()
+            || self
+                .include_globs
+                .iter()
+                .any(|glob| glob::matches(glob, name)))
+            && !self
+                .exclude_globs
+                .iter()
+                .any(|glob| glob::matches(glob, name))
+    }
+
+    /// Checks that the configuration is usable for benchmarking.
+    pub fn validate(&self) -> anyhow::Result<()> {
+        if let Some(targets) = &self.make_targets {
+            if targets.is_empty() {
+                anyhow::bail!("'make-targets' must contain at least one target.");
+            }
+
+            if targets.iter().any(|target| target.trim().is_empty()) {
+                anyhow::bail!("'make-targets' must not contain empty targets.");
+            }
+        }
+
+        Ok(())
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -57,76 +193,245 @@ enum BenchResult {
         hyperfine: Hyperfine,
         wc_raw: usize,
         wc_btormc_dump: usize,
+        /// Sizes of all sampled dumps, only stored if they differ.
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        wc_btormc_dump_samples: Vec<usize>,
+        /// Characters of the BTOR2 file per second of mean run time of `btormc`.
+        #[serde(default)]
+        chars_per_second: f64,
+        /// Arguments of the rotor run that generated the file, after expanding variables.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        rotor_args: Option<String>,
     },
     Failed {
         output: String,
         hyperfine: Hyperfine,
         wc_raw: usize,
         wc_btormc_dump: usize,
+        /// Sizes of all sampled dumps, only stored if they differ.
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        wc_btormc_dump_samples: Vec<usize>,
+        /// Arguments of the rotor run that generated the file, after expanding variables.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        rotor_args: Option<String>,
+    },
+}
+
+/// Version of the format of results files. It is increased whenever results files written by
+/// older versions of periscope can't be read as they are anymore. Files without a version are
+/// from before the format was versioned and are read as version 0.
+pub const RESULTS_SCHEMA_VERSION: u64 = 1;
+
+/// Contents of a results file. Older results files only contain the results without metadata.
+/// Metadata of a loaded file is not used, it is replaced when the results are written again.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum StoredResults {
+    WithMetadata {
+        results: HashMap<String, BenchResult>,
+    },
+    Plain(HashMap<String, BenchResult>),
+}
+
+/// Event that happens during benchmarking. Events are reported as soon as they happen, so that
+/// the progress of long benchmark sweeps can be observed.
+#[derive(Debug, Clone)]
+pub enum BenchEvent {
+    /// Rotor run with the given name started, files are generated and benchmarked next.
+    RunStarted { name: String },
+
+    /// Benchmarking of the file started.
+    Started { file: PathBuf },
+
+    /// `btormc` found a counterexample for the file.
+    Finished {
+        file: PathBuf,
+        wc_raw: usize,
+        wc_btormc_dump: usize,
+        props: String,
+        steps: usize,
+        /// Mean run time of `btormc` in seconds.
+        mean_time: f64,
+        /// Characters of the BTOR2 file per second of mean run time.
+        chars_per_second: f64,
     },
+
+    /// `btormc` did not produce a counterexample for the file.
+    Failed { file: PathBuf, output: String },
+
+    /// `btormc` did not finish in the configured timeout.
+    TimedOut { file: PathBuf },
+
+    /// Estimated time until all outstanding benchmarks are completed.
+    Progress { remaining: Duration },
+}
+
+/// Unit in which durations are printed. Results files always store seconds.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum TimeUnit {
+    /// Milliseconds.
+    Ms,
+    /// Seconds.
+    S,
+    /// Milliseconds below one second, seconds otherwise.
+    #[default]
+    Auto,
+}
+
+/// Formats a duration given in seconds, e.g. '1.23s' or '456ms'.
+pub fn format_time(seconds: f64, unit: TimeUnit) -> String {
+    match unit {
+        TimeUnit::Ms => format!("{:.1}ms", seconds * 1000.0),
+        TimeUnit::S => format!("{seconds:.3}s"),
+        TimeUnit::Auto if seconds < 0.001 => format!("{:.2}ms", seconds * 1000.0),
+        TimeUnit::Auto if seconds < 1.0 => format!("{:.0}ms", seconds * 1000.0),
+        TimeUnit::Auto => format!("{seconds:.2}s"),
+    }
+}
+
+/// Prints the benchmark events to stdout, with durations in the given unit. This is the default
+/// way of reporting the events.
+pub fn print_event(event: BenchEvent, time_unit: TimeUnit) {
+    match event {
+        BenchEvent::RunStarted { name } => {
+            if !output::is_quiet() {
+                println!("\nRunning '{name}':")
+            }
+        }
+        BenchEvent::Started { .. } => {}
+        BenchEvent::Finished {
+            file,
+            wc_raw,
+            wc_btormc_dump,
+            props,
+            steps,
+            mean_time,
+            chars_per_second,
+        } => println!(
+            "{}:\n\t{} characters, {} characters in dump.\n\tFound {} in {} steps.\n\tMean time: {} ({:.0} characters/s)",
+            file_name(&file),
+            wc_raw,
+            wc_btormc_dump,
+            props,
+            steps,
+            format_time(mean_time, time_unit),
+            chars_per_second
+        ),
+        BenchEvent::Failed { file, output } => {
+            eprintln!(
+                "{}:\n\tFailed parsing btor witness format: \n{}",
+                file_name(&file),
+                output
+            )
+        }
+        BenchEvent::TimedOut { file } => println!("{}:\n\tTimed out.", file_name(&file)),
+        BenchEvent::Progress { remaining } => {
+            if !output::is_quiet() {
+                println!("~{} remaining", progress::format_eta(remaining))
+            }
+        }
+    }
+}
+
+fn file_name(path: &Path) -> &str {
+    path.file_name().and_then(OsStr::to_str).unwrap_or_default()
 }
 
 /// Collects all `*.btor2` files in the given path and runs the `btormc` on them, benchmarking the
-/// runs.
+/// runs. Progress is reported through `on_event`, see [`print_event`] for the default reporting.
 pub fn run_benches(
-    path: PathBuf,
+    paths: Vec<PathBuf>,
     bench_config: BenchConfig,
     make_target: Option<String>,
+    on_event: &mut dyn FnMut(BenchEvent),
 ) -> anyhow::Result<()> {
-    let dot_periscope = create_dot_periscope();
-    let mut stdout = std::io::stdout().lock();
+    // created only once something is written into it
+    let dot_periscope = PathBuf::from(".periscope/bench");
+
+    interrupt::install_handler();
+    check_threads(&bench_config);
 
     if bench_config.runs.is_empty() {
-        bench_file_or_dir(path, &dot_periscope, bench_config, &mut stdout)
+        bench_files_or_dirs(paths, &dot_periscope, bench_config, on_event)
     } else {
-        run_benches_with_rotor(path, bench_config, &dot_periscope, make_target)
+        let selfie_dir = paths
+            .into_iter()
+            .next()
+            .context("Selfie directory is required when running rotor.")?;
+
+        run_benches_with_rotor(
+            selfie_dir,
+            bench_config,
+            &dot_periscope,
+            make_target,
+            on_event,
+        )
+    }
+}
+
+/// Warns if a btormc run may use more threads than there are cores, which makes the measured
+/// times unreliable. Benchmarks run one after another, so only the threads of a single run count.
+fn check_threads(config: &BenchConfig) {
+    let (Some(threads), Ok(cores)) = (config.btormc_threads, std::thread::available_parallelism())
+    else {
+        return;
+    };
+
+    if threads as usize > cores.get() {
+        output::warn(format!(
+            "{threads} btormc threads use more threads than the {cores} available cores."
+        ));
     }
 }
 
-fn bench_file_or_dir(
-    path: PathBuf,
+/// Benchmarks the given files and the BTOR2 files in the given directories, in order. Files that
+/// are listed more than once are only benchmarked the first time.
+fn bench_files_or_dirs(
+    paths: Vec<PathBuf>,
     dot_periscope: &Path,
     bench_config: BenchConfig,
-    stdout: &mut StdoutLock,
+    on_event: &mut dyn FnMut(BenchEvent),
 ) -> anyhow::Result<()> {
+    let metadata = Metadata::collect(&bench_config, None);
     let (mut results, results_path) =
-        load_or_create_results(dot_periscope, bench_config.results_path);
+        load_or_create_results(dot_periscope, bench_config.results_path.clone())?;
 
-    let mut paths: Vec<PathBuf> = Vec::new();
-
-    if path.is_file() {
-        paths.push(path);
-    } else {
-        paths.extend(
-            std::fs::read_dir(&path)
-                .expect("Could not open directory.")
-                .flat_map(|e| e.ok().map(|e| e.path())),
-        );
-    }
+    let mut files = Vec::new();
 
     for path in paths {
-        let bench_result = self::bench_file(
-            &path,
-            dot_periscope,
-            stdout,
-            bench_config.timeout,
-            &bench_config.btormc_flags,
-        )?;
-
-        let filename = path
-            .file_name()
-            .and_then(OsStr::to_str)
-            .map(String::from)
-            .expect("Failed to get filename.");
-
-        results.insert(filename, bench_result);
+        let collected = if path.is_file() {
+            vec![path]
+        } else {
+            collect_btor_files(&path, bench_config.recursive)?
+                .into_iter()
+                .filter(|file| bench_config.selects(file))
+                .collect()
+        };
+
+        for file in collected {
+            if !files.contains(&file) {
+                files.push(file);
+            }
+        }
     }
 
-    let mut results_file = File::create(results_path).unwrap();
-    serde_json::to_writer_pretty(&mut results_file, &results)
-        .expect("Failed serializing results into the results file.");
+    let mut progress = Progress::default();
+    progress.set_remaining(files.len());
+
+    let benched = bench_files(
+        files,
+        dot_periscope,
+        &bench_config,
+        None,
+        &mut results,
+        &mut progress,
+        on_event,
+    );
 
-    Ok(())
+    // completed results are kept even if benchmarking was stopped
+    write_results(&results_path, &results, bench_config.sort_by, &metadata)?;
+
+    benched
 }
 
 fn run_benches_with_rotor(
@@ -134,113 +439,328 @@ fn run_benches_with_rotor(
     config: BenchConfig,
     dot_periscope: &Path,
     make_target: Option<String>,
+    on_event: &mut dyn FnMut(BenchEvent),
 ) -> anyhow::Result<()> {
-    let mut stdout = std::io::stdout().lock();
+    let mut progress = Progress::default();
+    let runs = config.runs.len();
+
+    let mut metadata = Metadata::collect(&config, Some(&selfie_dir));
+    metadata.selfie_tree = config
+        .verify_tree
+        .then(|| tree::inspect(&selfie_dir))
+        .transpose()?;
 
-    for (name, rotor_args) in config.runs {
-        println!("\nRunning '{name}':");
+    for (run_idx, (name, rotor_args)) in config.runs.iter().enumerate() {
+        on_event(BenchEvent::RunStarted { name: name.clone() });
+        metadata.rotor_args = Some(rotor_args.clone());
 
         // run rotor with the given config
-        rotor::run_rotor(&selfie_dir, &rotor_args, &make_target)?;
-
-        // collect filtered files
-        let files: Vec<PathBuf> = std::fs::read_dir(selfie_dir.join("examples").join("symbolic"))?
-            .filter_map(|entry| {
-                // only files
-                entry
-                    .ok()
-                    .and_then(|e| e.path().is_file().then(|| e.path()))
-            })
-            .filter(|p| {
-                config
-                    .files
-                    .iter()
-                    .any(|el| el == p.file_name().and_then(OsStr::to_str).unwrap_or_default())
-            })
-            .collect();
-
-        // ensure results dir exists:
-        let results_dir = dot_periscope.join("results");
-        std::fs::create_dir_all(&results_dir).with_context(|| {
-            format!(
-                "Failed creating results directory at '{}'.",
-                results_dir.display()
-            )
-        })?;
-        // create results file
-        let results_path = results_dir.join(format!("{}.json", name));
-        let (mut results, results_path) = load_or_create_results(dot_periscope, Some(results_path));
-
-        for file in files {
-            let bench_result = bench_file(
-                &file,
-                dot_periscope,
-                &mut stdout,
-                config.timeout,
-                &config.btormc_flags,
-            )
-            .with_context(|| format!("Failed benching file {}", file.display()))?;
+        match &config.make_targets {
+            Some(targets) => rotor::run_make_targets(&selfie_dir, rotor_args, targets)?,
+            None => rotor::run_rotor(&selfie_dir, rotor_args, &make_target)?,
+        }
+
+        let files = generated_files(&selfie_dir, &config, name)?;
+
+        // results dir is created when the results are written
+        let results_path = dot_periscope.join("results").join(format!("{}.json", name));
+        let (mut results, results_path) =
+            load_or_create_results(dot_periscope, Some(results_path))?;
+
+        // assume the remaining runs produce as many files as this one
+        progress.set_remaining(files.len() * (runs - run_idx));
+
+        let benched = bench_files(
+            files,
+            dot_periscope,
+            &config,
+            Some(&rotor::expand_env_vars(rotor_args)?),
+            &mut results,
+            &mut progress,
+            on_event,
+        );
+
+        // completed results are kept even if benchmarking was stopped
+        write_results(&results_path, &results, config.sort_by, &metadata)?;
+        benched?;
+    }
 
-            let filename = file
-                .file_name()
-                .and_then(OsStr::to_str)
-                .map(String::from)
-                .expect("Failed to get filename.");
+    Ok(())
+}
+
+/// Files that rotor generated for the run, filtered by the configured files and then by the
+/// include and exclude globs. All generated files are benchmarked if no files are configured.
+fn generated_files(
+    selfie_dir: &Path,
+    config: &BenchConfig,
+    run: &str,
+) -> anyhow::Result<Vec<PathBuf>> {
+    let generated = collect_btor_files(
+        &selfie_dir.join("examples").join("symbolic"),
+        config.recursive,
+    )?;
+
+    for file in &config.files {
+        if !generated.iter().any(|path| file_name(path) == file) {
+            output::warn(format!("rotor run '{run}' did not generate '{file}'."));
+        }
+    }
+
+    Ok(generated
+        .into_iter()
+        .filter(|path| {
+            config.files.is_empty() || config.files.iter().any(|file| file == file_name(path))
+        })
+        .filter(|path| config.selects(path))
+        .collect())
+}
+
+const INTERRUPTED_MESSAGE: &str =
+    "Benchmarking was interrupted by Ctrl-C. Results of completed benchmarks were kept.";
+
+/// Benchmarks the given files one after another, storing the results by file name. With
+/// `fail_fast`, an error is returned after the first failed benchmark.
+fn bench_files(
+    files: Vec<PathBuf>,
+    dot_periscope: &Path,
+    config: &BenchConfig,
+    rotor_args: Option<&str>,
+    results: &mut HashMap<String, BenchResult>,
+    progress: &mut Progress,
+    on_event: &mut dyn FnMut(BenchEvent),
+) -> anyhow::Result<()> {
+    if !files.is_empty() {
+        ensure_dir(dot_periscope)?;
+    }
 
-            results.insert(filename, bench_result);
+    for file in files {
+        if interrupt::requested() {
+            anyhow::bail!(INTERRUPTED_MESSAGE);
         }
 
-        let mut results_file = File::create(&results_path)
-            .with_context(|| format!("Failed creating '{}'", results_path.display()))?;
-        serde_json::to_writer_pretty(&mut results_file, &results)
-            .context("Failed serializing results into the results file.")?;
+        on_event(BenchEvent::Started { file: file.clone() });
+
+        let started = Instant::now();
+        let bench_result = match bench_file(&file, dot_periscope, config, rotor_args) {
+            Ok(result) => result,
+            Err(_) if interrupt::requested() => anyhow::bail!(INTERRUPTED_MESSAGE),
+            Err(err) => return Err(err.context(format!("Failed benching file {}", file.display()))),
+        };
+
+        progress.finish_one(started.elapsed());
+
+        let event = bench_result.to_event(file.clone(), config.timeout);
+        let stop = config.fail_fast && config.fail_on.matches(&event);
+
+        on_event(event);
+
+        if let Some(remaining) = progress.eta() {
+            on_event(BenchEvent::Progress { remaining });
+        }
+
+        let filename = file
+            .file_name()
+            .and_then(OsStr::to_str)
+            .map(String::from)
+            .expect("Failed to get filename.");
+
+        results.insert(filename, bench_result);
+
+        if stop {
+            anyhow::bail!(
+                "Benchmark of '{}' failed, stopping because of 'fail-fast'.",
+                file.display()
+            );
+        }
     }
 
     Ok(())
 }
 
-fn create_dot_periscope() -> PathBuf {
-    let dot_periscope = PathBuf::from(".periscope/bench");
+/// Collects all BTOR2 files in `dir`, sorted by path so that benchmarks run in a reproducible
+/// order. Subdirectories are searched as well if `recursive` is set.
+fn collect_btor_files(dir: &Path, recursive: bool) -> anyhow::Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+
+    let entries = std::fs::read_dir(dir)
+        .with_context(|| format!("Could not open directory '{}'.", dir.display()))?;
+
+    for entry in entries {
+        let entry = entry?;
+        let path = entry.path();
 
-    if !dot_periscope.exists() || !dot_periscope.is_dir() {
-        std::fs::create_dir_all(&dot_periscope)
-            .unwrap_or_else(|err| panic!("Failed creating '{}': {}", dot_periscope.display(), err))
+        if path.is_dir() {
+            if recursive {
+                files.extend(collect_btor_files(&path, recursive)?);
+            }
+        } else if entry.is_btor2() {
+            files.push(path);
+        }
     }
 
-    dot_periscope
+    files.sort();
+
+    Ok(files)
 }
 
+/// Creates the directory if it does not exist yet.
+fn ensure_dir(dir: &Path) -> anyhow::Result<()> {
+    if !dir.is_dir() {
+        std::fs::create_dir_all(dir)
+            .with_context(|| format!("Failed creating '{}'.", dir.display()))?;
+    }
+
+    Ok(())
+}
+
+/// Loads the results stored in the results file, or starts with no results if the file can't be
+/// read. Fails if the file was written by an incompatible version of periscope, so that its
+/// results are not overwritten.
 fn load_or_create_results(
     dot_periscope: &Path,
     results_path: Option<PathBuf>,
-) -> (HashMap<String, BenchResult>, PathBuf) {
+) -> anyhow::Result<(HashMap<String, BenchResult>, PathBuf)> {
     let results_path = results_path.unwrap_or_else(|| dot_periscope.join("results.json"));
 
-    let results = File::open(&results_path)
+    let stored = File::open(&results_path)
         .context("Failed reading file.")
-        .and_then(|f| serde_json::from_reader(&f).context("Falied deserializing results file."))
+        .and_then(|f| {
+            serde_json::from_reader::<_, serde_json::Value>(&f)
+                .context("Falied deserializing results file.")
+        });
+
+    if let Ok(stored) = &stored {
+        check_schema_version(stored, &results_path)?;
+    }
+
+    let results = stored
+        .and_then(|stored| {
+            serde_json::from_value(stored).context("Falied deserializing results file.")
+        })
+        .map(|stored| match stored {
+            StoredResults::WithMetadata { results } | StoredResults::Plain(results) => results,
+        })
         .inspect_err(|err| {
-            eprintln!(
-                "Deserialization of '{}' failed: {}",
+            output::warn(format!(
+                "deserialization of '{}' failed: {}",
                 results_path.display(),
                 err
-            )
+            ))
         })
         .unwrap_or_default();
 
-    (results, results_path)
+    Ok((results, results_path))
+}
+
+/// Checks that the results file was written in a format this version of periscope can read.
+/// Results files without a version are read in their older format.
+fn check_schema_version(stored: &serde_json::Value, results_path: &Path) -> anyhow::Result<()> {
+    let Some(version) = stored.get("schema_version") else {
+        return Ok(());
+    };
+
+    match version.as_u64() {
+        Some(RESULTS_SCHEMA_VERSION) => Ok(()),
+        Some(version) => anyhow::bail!(
+            "Results file '{}' was written by an incompatible periscope version (schema version {version}, expected {RESULTS_SCHEMA_VERSION}). Move it away or choose another 'results-path'.",
+            results_path.display()
+        ),
+        None => anyhow::bail!(
+            "Results file '{}' has an invalid schema version '{version}'.",
+            results_path.display()
+        ),
+    }
+}
+
+/// Writes the metadata and the results into the results file, results ordered as given by
+/// `sort_by`.
+fn write_results(
+    results_path: &Path,
+    results: &HashMap<String, BenchResult>,
+    sort_by: SortBy,
+    metadata: &Metadata,
+) -> anyhow::Result<()> {
+    let mut sorted: Vec<_> = results.iter().collect();
+    sorted.sort_by_key(|(name, _)| *name);
+
+    match sort_by {
+        SortBy::Name => {}
+        SortBy::Chars => sorted.sort_by_key(|(_, result)| std::cmp::Reverse(result.wc_raw())),
+        SortBy::Time => sorted.sort_by(|(_, a), (_, b)| b.mean_time().total_cmp(&a.mean_time())),
+        SortBy::Speed => sorted.sort_by(|(_, a), (_, b)| b.speed().total_cmp(&a.speed())),
+    }
+
+    if let Some(parent) = results_path.parent() {
+        ensure_dir(parent)?;
+    }
+
+    // written next to the results file first, so that an interruption can't leave it half-written
+    let tmp_path = results_path.with_extension("json.tmp");
+    let mut results_file = File::create(&tmp_path)
+        .with_context(|| format!("Failed creating '{}'", tmp_path.display()))?;
+    let results_file_contents = ResultsFile {
+        schema_version: RESULTS_SCHEMA_VERSION,
+        metadata,
+        results: SortedResults(sorted),
+    };
+
+    serde_json::to_writer_pretty(&mut results_file, &results_file_contents)
+        .context("Failed serializing results into the results file.")?;
+
+    std::fs::rename(&tmp_path, results_path)
+        .with_context(|| format!("Failed writing '{}'", results_path.display()))
+}
+
+#[derive(Serialize)]
+struct ResultsFile<'a> {
+    schema_version: u64,
+    metadata: &'a Metadata,
+    results: SortedResults<'a>,
+}
+
+/// Results in a fixed order, serialized as a map from file name to result.
+struct SortedResults<'a>(Vec<(&'a String, &'a BenchResult)>);
+
+impl Serialize for SortedResults<'_> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_map(self.0.iter().copied())
+    }
 }
 
 fn bench_file(
     path: impl AsRef<Path>,
     dot_periscope: &Path,
-    stdout: &mut std::io::StdoutLock,
-    timeout: Option<u128>,
-    btormc_flags: &Option<String>,
+    config: &BenchConfig,
+    rotor_args: Option<&str>,
 ) -> anyhow::Result<BenchResult> {
     let path = path.as_ref();
     let wc_raw = wc::char_count_in_file(path)?;
-    let wc_of_dump = wc::char_count_in_dump(path)?;
+
+    // the dump is only sampled repeatedly if asked for, since it should be deterministic. Warmup
+    // runs are not measured, but their dumps are checked as well.
+    let dumps = config.samples.unwrap_or(1) + config.warmup.unwrap_or(0);
+    let mut wc_btormc_dump_samples = (0..dumps)
+        .map(|_| wc::char_count_in_dump(path))
+        .collect::<anyhow::Result<Vec<_>>>()?;
+    let wc_of_dump = wc_btormc_dump_samples[0];
+
+    if wc_btormc_dump_samples
+        .iter()
+        .all(|count| *count == wc_of_dump)
+    {
+        wc_btormc_dump_samples.clear();
+    } else {
+        output::warn(format!(
+            "'{}' produced btormc dumps of different sizes: {}. The model seems to produce \
+            nondeterministic output.",
+            path.display(),
+            wc_btormc_dump_samples
+                .iter()
+                .map(usize::to_string)
+                .collect::<Vec<_>>()
+                .join(", ")
+        ));
+    }
 
     debug_assert!(dot_periscope.exists());
 
@@ -248,22 +768,14 @@ fn bench_file(
 
     let hyperfine_out_path = dot_periscope.join(format!("{file_name}_hyperfine_output"));
     let hyperfine_json_path = dot_periscope.join(format!("{file_name}_hyperfine.json"));
-    let hyperfine = hyperfine::run(
-        path,
-        &hyperfine_out_path,
-        hyperfine_json_path,
-        btormc_flags,
-        timeout,
-    )?;
+    let hyperfine = hyperfine::run(path, &hyperfine_out_path, hyperfine_json_path, config)?;
 
     let mut props_in_steps = {
-        if let Ok(witness) =
-            btor::parse_btor_witness(File::open(&hyperfine_out_path)?, File::open(path).ok())
-                .inspect_err(|_| {
-                    let witness = std::fs::read_to_string(&hyperfine_out_path).unwrap_or_default();
-                    format!("Failed parsing btor witness format: \n{}", witness);
-                })
-        {
+        if let Ok(WitnessOutcome::Sat(witness)) = btor::parse_btor_witness(
+            File::open(&hyperfine_out_path)?,
+            File::open(path).ok(),
+            &btor::ParseOptions::default(),
+        ) {
             witness.props_in_steps()
         } else {
             return Ok(BenchResult::Failed {
@@ -271,6 +783,8 @@ fn bench_file(
                 hyperfine,
                 wc_raw,
                 wc_btormc_dump: wc_of_dump,
+                wc_btormc_dump_samples,
+                rotor_args: rotor_args.map(String::from),
             });
         }
     };
@@ -299,20 +813,7 @@ fn bench_file(
         .collect::<anyhow::Result<Vec<_>>>()?;
 
     let steps = props_in_steps[0].1;
-
-    if props_in_steps.len() == 1 {
-        let _ = writeln!(
-            stdout,
-            "{}:\n\t{} characters, {} characters in dump.\n\tFound {} in {} steps.",
-            path.file_name()
-                .and_then(OsStr::to_str)
-                .context("Invalid path to btor2 file.")?,
-            wc_raw,
-            wc_of_dump,
-            props_in_steps[0].0.formatted_string(),
-            props_in_steps[0].1
-        );
-    }
+    let mean_time = hyperfine.mean_time();
 
     Ok(BenchResult::Success {
         props,
@@ -320,9 +821,90 @@ fn bench_file(
         hyperfine,
         wc_raw,
         wc_btormc_dump: wc_of_dump,
+        wc_btormc_dump_samples,
+        chars_per_second: if mean_time > 0.0 {
+            wc_raw as f64 / mean_time
+        } else {
+            0.0
+        },
+        rotor_args: rotor_args.map(String::from),
     })
 }
 
+impl BenchResult {
+    fn wc_raw(&self) -> usize {
+        match self {
+            BenchResult::Success { wc_raw, .. } | BenchResult::Failed { wc_raw, .. } => *wc_raw,
+        }
+    }
+
+    /// Mean run time of `btormc` in seconds.
+    fn mean_time(&self) -> f64 {
+        match self {
+            BenchResult::Success { hyperfine, .. } | BenchResult::Failed { hyperfine, .. } => {
+                hyperfine.mean_time()
+            }
+        }
+    }
+
+    /// Characters of the BTOR2 file per second of run time, 0 for failed benchmarks.
+    fn speed(&self) -> f64 {
+        match self {
+            BenchResult::Success {
+                chars_per_second, ..
+            } => *chars_per_second,
+            BenchResult::Failed { .. } => 0.0,
+        }
+    }
+
+    fn to_event(&self, file: PathBuf, timeout: Option<u128>) -> BenchEvent {
+        match self {
+            BenchResult::Success {
+                props,
+                steps,
+                wc_raw,
+                wc_btormc_dump,
+                ..
+            } => BenchEvent::Finished {
+                file,
+                wc_raw: *wc_raw,
+                wc_btormc_dump: *wc_btormc_dump,
+                props: props
+                    .iter()
+                    .map(Prop::to_string)
+                    .collect::<Vec<_>>()
+                    .join(", "),
+                steps: *steps,
+                mean_time: self.mean_time(),
+                chars_per_second: self.speed(),
+            },
+            BenchResult::Failed { hyperfine, .. } if timeout.is_some() && hyperfine.timed_out() => {
+                BenchEvent::TimedOut { file }
+            }
+            BenchResult::Failed { output, .. } => BenchEvent::Failed {
+                file,
+                output: output.clone(),
+            },
+        }
+    }
+}
+
+impl std::fmt::Display for Prop {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.kind {
+            btor::PropKind::Bad => write!(f, "Bad at {}", self.idx)?,
+            btor::PropKind::Justice => write!(f, "Justice at {}", self.idx)?,
+            btor::PropKind::Constraint => write!(f, "Constraint at {}", self.idx)?,
+        }
+
+        if let Some(name) = &self.name {
+            write!(f, " named '{}' with nid: {}", name, self.node)?;
+        }
+
+        Ok(())
+    }
+}
+
 trait IsBtor2 {
     fn is_btor2(&self) -> bool;
 }
@@ -335,3 +917,66 @@ impl IsBtor2 for DirEntry {
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Directory under the system temp directory that is removed when dropped.
+    struct TempDir(PathBuf);
+
+    impl TempDir {
+        fn new(name: &str) -> Self {
+            let dir = std::env::temp_dir().join(format!("periscope-{name}-{}", std::process::id()));
+            let _ = std::fs::remove_dir_all(&dir);
+            std::fs::create_dir_all(&dir).expect("temp dir is created");
+
+            Self(dir)
+        }
+
+        fn touch(&self, path: &str) {
+            let path = self.0.join(path);
+            std::fs::create_dir_all(path.parent().expect("path has a parent"))
+                .expect("parent is created");
+            File::create(path).expect("file is created");
+        }
+    }
+
+    impl Drop for TempDir {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_dir_all(&self.0);
+        }
+    }
+
+    #[test]
+    fn btor_files_are_collected_sorted_and_recursively() {
+        let dir = TempDir::new("collect");
+        dir.touch("b.btor2");
+        dir.touch("a.btor2");
+        dir.touch("notes.c");
+        dir.touch("nested/c.btor2");
+        dir.touch("nested/deeper/a.btor2");
+        dir.touch("nested/main.c");
+
+        let names = |files: Vec<PathBuf>| -> Vec<String> {
+            files
+                .iter()
+                .map(|file| file.strip_prefix(&dir.0).unwrap().display().to_string())
+                .collect()
+        };
+
+        let flat = collect_btor_files(&dir.0, false).unwrap();
+        assert_eq!(names(flat), ["a.btor2", "b.btor2"]);
+
+        let recursive = collect_btor_files(&dir.0, true).unwrap();
+        assert_eq!(
+            names(recursive),
+            [
+                "a.btor2",
+                "b.btor2",
+                "nested/c.btor2",
+                "nested/deeper/a.btor2"
+            ]
+        );
+    }
+}
@@ -0,0 +1,51 @@
+use std::time::Duration;
+
+/// Tracks the durations of completed benchmarks to estimate how long the remaining ones take.
+#[derive(Debug, Default)]
+pub struct Progress {
+    completed: u32,
+    elapsed: Duration,
+    remaining: usize,
+}
+
+impl Progress {
+    /// Sets the number of benchmarks that are still outstanding.
+    pub fn set_remaining(&mut self, remaining: usize) {
+        self.remaining = remaining;
+    }
+
+    /// Records a completed benchmark that took `duration`.
+    pub fn finish_one(&mut self, duration: Duration) {
+        self.completed += 1;
+        self.elapsed += duration;
+        self.remaining = self.remaining.saturating_sub(1);
+    }
+
+    /// Estimated time until all outstanding benchmarks are completed, based on the average
+    /// duration of the completed ones. Returns `None` if nothing was completed yet or nothing is
+    /// outstanding.
+    pub fn eta(&self) -> Option<Duration> {
+        if self.remaining == 0 {
+            return None;
+        }
+
+        let average = self.elapsed.checked_div(self.completed)?;
+        let remaining = u32::try_from(self.remaining).unwrap_or(u32::MAX);
+
+        Some(average.saturating_mul(remaining))
+    }
+}
+
+/// Formats the duration as e.g. `6m12s`.
+pub fn format_eta(duration: Duration) -> String {
+    let secs = duration.as_secs();
+    let (hours, mins, secs) = (secs / 3600, secs / 60 % 60, secs % 60);
+
+    if hours > 0 {
+        format!("{hours}h{mins:02}m{secs:02}s")
+    } else if mins > 0 {
+        format!("{mins}m{secs:02}s")
+    } else {
+        format!("{secs}s")
+    }
+}
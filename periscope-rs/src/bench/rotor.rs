@@ -1,25 +1,120 @@
 use std::{path::Path, process::Command};
 
+use anyhow::Context;
+
+use super::interrupt;
+
 pub fn run_rotor(
     selfie_dir: &Path,
     rotor_args: &str,
     make_target: &Option<String>,
 ) -> anyhow::Result<()> {
+    let rotor_args = expand_env_vars(rotor_args)?;
+
     // make sure we start fresh
-    Command::new("make")
-        .arg("clean")
-        .current_dir(selfie_dir)
-        .spawn()?
-        .wait()?;
+    let status = interrupt::run(Command::new("make").arg("clean").current_dir(selfie_dir))?;
+
+    if !status.success() {
+        anyhow::bail!("Running 'make clean' failed: {status}");
+    }
 
     let make_target = make_target.as_deref().unwrap_or("rotor-symbolic");
 
-    Command::new("make")
-        .arg(make_target)
-        .arg(format!("rotor={}", rotor_args))
-        .current_dir(selfie_dir)
-        .spawn()?
-        .wait()?;
+    let status = interrupt::run(
+        Command::new("make")
+            .arg(make_target)
+            .arg(format!("rotor={}", rotor_args))
+            .current_dir(selfie_dir),
+    )?;
+
+    if !status.success() {
+        anyhow::bail!("Running 'make {make_target}' failed: {status}");
+    }
 
     Ok(())
 }
+
+/// Runs the given `make` targets in order inside of the selfie directory, passing the rotor
+/// arguments to each of them.
+pub fn run_make_targets(
+    selfie_dir: &Path,
+    rotor_args: &str,
+    make_targets: &[String],
+) -> anyhow::Result<()> {
+    let rotor_args = expand_env_vars(rotor_args)?;
+
+    for target in make_targets {
+        let status = interrupt::run(
+            Command::new("make")
+                .arg(target)
+                .arg(format!("rotor={}", rotor_args))
+                .current_dir(selfie_dir),
+        )?;
+
+        if !status.success() {
+            anyhow::bail!("Running 'make {target}' failed: {status}");
+        }
+    }
+
+    Ok(())
+}
+
+/// Replaces `${VAR}` in the rotor arguments with the value of the environment variable `VAR`.
+pub fn expand_env_vars(rotor_args: &str) -> anyhow::Result<String> {
+    let mut expanded = String::with_capacity(rotor_args.len());
+    let mut rest = rotor_args;
+
+    while let Some(start) = rest.find("${") {
+        expanded.push_str(&rest[..start]);
+
+        let var = &rest[start + 2..];
+        let end = var
+            .find('}')
+            .with_context(|| format!("Unterminated '${{' in rotor arguments '{rotor_args}'."))?;
+
+        let name = &var[..end];
+        let value = std::env::var(name).with_context(|| {
+            format!("Environment variable '{name}' used in rotor arguments is not set.")
+        })?;
+
+        expanded.push_str(&value);
+        rest = &var[end + 1..];
+    }
+
+    expanded.push_str(rest);
+
+    Ok(expanded)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn set_variable_is_expanded() {
+        std::env::set_var("PERISCOPE_TEST_CODEWORD_SIZE", "16");
+
+        let expanded = expand_env_vars("0 -codewordsize ${PERISCOPE_TEST_CODEWORD_SIZE}").unwrap();
+        assert_eq!(expanded, "0 -codewordsize 16");
+    }
+
+    #[test]
+    fn unset_variable_is_rejected() {
+        std::env::remove_var("PERISCOPE_TEST_UNSET");
+
+        let err = expand_env_vars("0 ${PERISCOPE_TEST_UNSET}").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Environment variable 'PERISCOPE_TEST_UNSET' used in rotor arguments is not set."
+        );
+    }
+
+    #[test]
+    fn unterminated_variable_is_rejected() {
+        let err = expand_env_vars("0 ${PERISCOPE_TEST").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Unterminated '${' in rotor arguments '0 ${PERISCOPE_TEST'."
+        );
+    }
+}
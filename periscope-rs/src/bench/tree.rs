@@ -0,0 +1,71 @@
+use std::{path::Path, process::Command};
+
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+
+use crate::output;
+
+/// State of the git working tree of the selfie directory that produced the benchmark results.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SelfieTree {
+    /// Commit that is checked out.
+    pub commit: String,
+
+    /// Output of `git diff --stat HEAD`, empty if tracked files are not modified.
+    pub diff_stat: String,
+
+    /// Untracked files that are not ignored by git.
+    pub untracked: Vec<String>,
+}
+
+/// Inspects the git working tree of the selfie directory. Prints a warning if it contains
+/// modified or untracked files, since these could affect the build of the benchmarked models.
+pub fn inspect(selfie_dir: &Path) -> anyhow::Result<SelfieTree> {
+    let commit = git(selfie_dir, &["rev-parse", "HEAD"])?.trim().to_string();
+    let diff_stat = git(selfie_dir, &["diff", "--stat", "HEAD"])?;
+    let untracked: Vec<String> = git(selfie_dir, &["ls-files", "--others", "--exclude-standard"])?
+        .lines()
+        .map(String::from)
+        .collect();
+
+    if !diff_stat.trim().is_empty() {
+        output::warn(format!(
+            "selfie tree at '{}' has modified files:\n{}",
+            selfie_dir.display(),
+            diff_stat.trim_end()
+        ));
+    }
+
+    if !untracked.is_empty() {
+        output::warn(format!(
+            "selfie tree at '{}' has untracked files that could affect the build:\n    {}",
+            selfie_dir.display(),
+            untracked.join("\n    ")
+        ));
+    }
+
+    Ok(SelfieTree {
+        commit,
+        diff_stat,
+        untracked,
+    })
+}
+
+pub(super) fn git(dir: &Path, args: &[&str]) -> anyhow::Result<String> {
+    let output = Command::new("git")
+        .args(args)
+        .current_dir(dir)
+        .output()
+        .context("Failed running 'git'.")?;
+
+    if !output.status.success() {
+        anyhow::bail!(
+            "Running 'git {}' in '{}' failed: {}",
+            args.join(" "),
+            dir.display(),
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+
+    String::from_utf8(output.stdout).context("Output of 'git' is not valid UTF-8.")
+}
@@ -6,6 +6,8 @@ use std::{
 
 use anyhow::Context;
 
+use crate::error::PeriscopeError;
+
 pub fn char_count_in_file(file: impl AsRef<Path>) -> anyhow::Result<usize> {
     let wc = Command::new("wc")
         .arg("-c")
@@ -20,7 +22,14 @@ pub fn char_count_in_dump(path: impl AsRef<Path>) -> anyhow::Result<usize> {
     let s = Command::new("btormc")
         .arg("-d")
         .arg(path.as_ref())
-        .output()?
+        .output()
+        .map_err(|err| match err.kind() {
+            std::io::ErrorKind::NotFound => PeriscopeError::BtormcNotFound,
+            _ => PeriscopeError::Tool {
+                tool: String::from("btormc"),
+                source: err,
+            },
+        })?
         .stdout;
 
     let mut wc_child = Command::new("wc")
@@ -39,14 +48,40 @@ pub fn char_count_in_dump(path: impl AsRef<Path>) -> anyhow::Result<usize> {
     parse_wc_output(&wc)
 }
 
+/// Parses the character count from the output of `wc -c`. The count is the first field of the
+/// output, which BSD `wc` right-aligns with leading spaces, e.g. `     123 file` instead of
+/// `123 file`.
 fn parse_wc_output(output: &[u8]) -> anyhow::Result<usize> {
-    let idx = output
-        .iter()
-        .position(|c| c == &b' ')
-        .context("Bad output from 'wc' command.")?;
+    let output = std::str::from_utf8(output).context("Bad output from 'wc' command.")?;
 
-    let wc = std::str::from_utf8(output[..idx].as_ref())?;
+    let wc = output
+        .split_whitespace()
+        .next()
+        .context("Bad output from 'wc' command.")?;
 
     wc.parse()
         .context("Could not parse output of 'wc' command.")
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn gnu_output_is_parsed() {
+        assert_eq!(parse_wc_output(b"123 file.btor2\n").unwrap(), 123);
+        assert_eq!(parse_wc_output(b"123 -\n").unwrap(), 123);
+    }
+
+    #[test]
+    fn bsd_output_with_padding_is_parsed() {
+        assert_eq!(parse_wc_output(b"     123 file.btor2\n").unwrap(), 123);
+        assert_eq!(parse_wc_output(b"     123\n").unwrap(), 123);
+    }
+
+    #[test]
+    fn empty_output_is_rejected() {
+        assert!(parse_wc_output(b"").is_err());
+        assert!(parse_wc_output(b"    \n").is_err());
+    }
+}
@@ -0,0 +1,201 @@
+use std::io::{BufRead, Write};
+
+use crate::{
+    btor::{Assignment, Flow, FlowPrinter, ReportOptions, Witness},
+    error::Result,
+};
+
+const HELP: &str = "\
+Commands:
+    l            list signals matching the current filter
+    /<text>      only list signals whose name contains <text>, '/' alone clears the filter
+    <number>     select signal with the given number from the list and show its flow
+    n, p         select next or previous signal
+    ], [         jump to next or previous change of the selected signal
+    h, ?         show this help
+    q            quit";
+
+/// Interactive, line based browser for the signal flows of a witness. Commands are read from
+/// `input`, one per line.
+pub fn browse(
+    witness: &Witness,
+    options: &ReportOptions,
+    input: impl BufRead,
+    out: &mut dyn Write,
+) -> Result<()> {
+    let inputs = (!options.no_input)
+        .then(|| witness.analyze_input_flow(options))
+        .transpose()?;
+    let states = (!options.no_state)
+        .then(|| witness.analyze_state_flow(options))
+        .transpose()?;
+
+    let signals: Vec<Signal> = [&inputs, &states]
+        .into_iter()
+        .flatten()
+        .flat_map(|flow| {
+            flow.signals().map(move |(name, assignments)| Signal {
+                flow,
+                name,
+                assignments,
+            })
+        })
+        .collect();
+
+    let mut browser = Browser {
+        signals,
+        printer: FlowPrinter::new(options),
+        filter: String::new(),
+        selected: None,
+        change: 0,
+    };
+
+    writeln!(out, "{HELP}\n")?;
+    browser.list(out)?;
+    prompt(out)?;
+
+    for line in input.lines() {
+        let line = line?;
+
+        match line.trim() {
+            "q" => break,
+            "" | "l" => browser.list(out)?,
+            "h" | "?" => writeln!(out, "{HELP}")?,
+            "n" => browser.step_signal(out, 1)?,
+            "p" => browser.step_signal(out, -1)?,
+            "]" => browser.step_change(out, 1)?,
+            "[" => browser.step_change(out, -1)?,
+            cmd if cmd.starts_with('/') => {
+                browser.filter = cmd[1..].trim().to_string();
+                browser.list(out)?;
+            }
+            cmd => match cmd.parse::<usize>() {
+                Ok(number) => browser.select_listed(out, number)?,
+                Err(_) => writeln!(out, "Unknown command '{cmd}', type 'h' for help.")?,
+            },
+        }
+
+        prompt(out)?;
+    }
+
+    Ok(())
+}
+
+fn prompt(out: &mut dyn Write) -> std::io::Result<()> {
+    write!(out, "> ")?;
+    out.flush()
+}
+
+struct Signal<'a> {
+    flow: &'a Flow,
+    name: &'a str,
+    assignments: &'a [(u64, Assignment)],
+}
+
+struct Browser<'a> {
+    signals: Vec<Signal<'a>>,
+    printer: FlowPrinter<'a>,
+    filter: String,
+    /// Index of the selected signal in `signals`.
+    selected: Option<usize>,
+    /// Index of the current change of the selected signal.
+    change: usize,
+}
+
+impl Browser<'_> {
+    /// Indices of signals that match the filter.
+    fn listed(&self) -> Vec<usize> {
+        (0..self.signals.len())
+            .filter(|idx| self.signals[*idx].name.contains(self.filter.as_str()))
+            .collect()
+    }
+
+    fn list(&self, out: &mut dyn Write) -> std::io::Result<()> {
+        let listed = self.listed();
+
+        if listed.is_empty() {
+            return writeln!(out, "No signals match '{}'.", self.filter);
+        }
+
+        for (number, idx) in listed.into_iter().enumerate() {
+            let signal = &self.signals[idx];
+            let marker = if self.selected == Some(idx) { '*' } else { ' ' };
+
+            writeln!(
+                out,
+                "{marker}{:>4}  {} {} ({} changes)",
+                number + 1,
+                signal.flow.flow_type().name(),
+                signal.name,
+                signal.assignments.len()
+            )?;
+        }
+
+        Ok(())
+    }
+
+    fn select_listed(&mut self, out: &mut dyn Write, number: usize) -> std::io::Result<()> {
+        match number
+            .checked_sub(1)
+            .and_then(|n| self.listed().get(n).copied())
+        {
+            Some(idx) => self.select(out, idx),
+            None => writeln!(out, "No signal with number {number} in the list."),
+        }
+    }
+
+    fn step_signal(&mut self, out: &mut dyn Write, offset: isize) -> std::io::Result<()> {
+        let listed = self.listed();
+
+        if listed.is_empty() {
+            return writeln!(out, "No signals match '{}'.", self.filter);
+        }
+
+        let position = self
+            .selected
+            .and_then(|selected| listed.iter().position(|idx| *idx == selected));
+
+        let position = match (position, offset.is_negative()) {
+            (Some(position), false) => (position + 1).min(listed.len() - 1),
+            (Some(position), true) => position.saturating_sub(1),
+            (None, _) => 0,
+        };
+
+        self.select(out, listed[position])
+    }
+
+    fn select(&mut self, out: &mut dyn Write, idx: usize) -> std::io::Result<()> {
+        let signal = &self.signals[idx];
+
+        self.selected = Some(idx);
+        self.change = 0;
+
+        self.printer
+            .print_signal(out, signal.flow, signal.name, signal.assignments)
+    }
+
+    fn step_change(&mut self, out: &mut dyn Write, offset: isize) -> std::io::Result<()> {
+        let Some(signal) = self.selected.map(|idx| &self.signals[idx]) else {
+            return writeln!(out, "No signal selected.");
+        };
+
+        let last = signal.assignments.len().saturating_sub(1);
+        let change = self.change.saturating_add_signed(offset).min(last);
+
+        if change == self.change {
+            writeln!(out, "No more changes in this direction.")?;
+        }
+
+        self.change = change;
+
+        writeln!(
+            out,
+            "    {} [{}/{}]: {}",
+            signal.name,
+            change + 1,
+            signal.assignments.len(),
+            self.printer
+                .render_change(signal.flow, signal.name, signal.assignments, change)
+        )
+    }
+}
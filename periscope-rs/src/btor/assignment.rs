@@ -3,11 +3,12 @@ use nom::{
     bytes::{self, complete},
     character, combinator, sequence,
 };
+use serde::{Deserialize, Serialize};
 use std::fmt::Write;
 
-use super::helpers;
+use super::{flow::FlowType, helpers, options::BitOrder};
 
-#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 pub enum AssignmentKind {
     /// Assignment to a bitvector.
     BitVec {
@@ -21,6 +22,9 @@ pub enum AssignmentKind {
     Array {
         /// Index in the array
         index: u64,
+        /// Number of bits of the index.
+        #[serde(default)]
+        index_bits: usize,
         /// Value of the bitvector at `index` at the given transition.
         value: u64,
         /// Number of bits the bitvector
@@ -29,31 +33,65 @@ pub enum AssignmentKind {
 }
 
 impl AssignmentKind {
+    /// Number of bits of the assigned bitvector value.
+    pub fn bits(self) -> usize {
+        match self {
+            AssignmentKind::BitVec { bits, .. } => bits,
+            AssignmentKind::Array { bits, .. } => bits,
+        }
+    }
+
     pub fn to_binary_string(self) -> String {
+        self.to_ordered_binary_string(BitOrder::Msb)
+    }
+
+    /// Like [`AssignmentKind::to_binary_string`], but with the bits written in the given order.
+    pub fn to_ordered_binary_string(self, order: BitOrder) -> String {
+        self.to_elided_binary_string(usize::MAX, order)
+    }
+
+    /// Like [`AssignmentKind::to_ordered_binary_string`], but values wider than `max_bits` only
+    /// show their first and last bits with '...' in between.
+    pub fn to_elided_binary_string(self, max_bits: usize, order: BitOrder) -> String {
         let (bits, extra) = match self {
             AssignmentKind::BitVec { bits, .. } => (bits, 0),
-            AssignmentKind::Array { bits, .. } => (bits * 2, 4),
+            AssignmentKind::Array {
+                bits, index_bits, ..
+            } => (bits + index_bits, 4),
         };
 
         let mut buf = String::with_capacity(bits + extra);
 
         let write_bits = |buf: &mut String, value, len: usize| {
-            (0..len).rev().for_each(|i| {
+            let mut digits = String::with_capacity(len);
+            let mut write_bit = |i: usize| {
                 let bit = (value >> i) & 1;
-                write!(buf, "{}", bit).expect("Writing to string is infallible.");
-            });
+                write!(digits, "{}", bit).expect("Writing to string is infallible.");
+            };
+
+            match order {
+                BitOrder::Msb => (0..len).rev().for_each(&mut write_bit),
+                BitOrder::Lsb => (0..len).for_each(&mut write_bit),
+            }
+
+            buf.push_str(&helpers::elide_middle(&digits, max_bits));
         };
 
         match self {
             AssignmentKind::BitVec { value, .. } => write_bits(&mut buf, value, bits),
-            AssignmentKind::Array { value, index, .. } => {
+            AssignmentKind::Array {
+                value,
+                index,
+                bits,
+                index_bits,
+            } => {
                 buf.push('[');
-                write_bits(&mut buf, index, bits / 2);
+                write_bits(&mut buf, index, index_bits);
                 buf.push(']');
 
                 buf.push_str(" -> ");
 
-                write_bits(&mut buf, value, bits / 2);
+                write_bits(&mut buf, value, bits);
             }
         };
 
@@ -61,15 +99,19 @@ impl AssignmentKind {
     }
 }
 
-#[derive(Clone, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 pub struct Assignment {
     pub kind: AssignmentKind,
     pub symbol: Option<String>,
+    /// Position of the input or state in the model as given by the witness, e.g. `2` in
+    /// `2 0101 x@0`.
+    #[serde(default)]
+    pub index: u64,
 }
 
 impl Assignment {
     pub fn parse(input: &str) -> nom::IResult<&str, Assignment> {
-        let (input, _uint) = helpers::uint(input)?;
+        let (input, index) = helpers::uint(input)?;
 
         let (input, _whitespace) = character::complete::space0(input)?;
 
@@ -86,10 +128,21 @@ impl Assignment {
             Assignment {
                 kind: assignment,
                 symbol: symbol.map(String::from),
+                index,
             },
         ))
     }
 
+    /// Name of the assigned signal. Assignments without a symbol are named by their position in
+    /// the witness, e.g. '#unnamed-@2' for the input at position 2, so that all assignments of the
+    /// same input or state form one flow.
+    pub fn name(&self, flow_type: FlowType) -> String {
+        match &self.symbol {
+            Some(symbol) => symbol.clone(),
+            None => format!("#unnamed-{}{}", flow_type.prefix(), self.index),
+        }
+    }
+
     pub fn get_value(&self) -> u64 {
         match self.kind {
             AssignmentKind::BitVec { value, .. } => value,
@@ -118,6 +171,13 @@ fn symbol(input: &str) -> nom::IResult<&str, &str> {
         symbol = &symbol[..idx];
     }
 
+    // states are suffixed with the step, e.g. 'state#2'
+    if let Some((name, step)) = symbol.rsplit_once('#') {
+        if !step.is_empty() && step.bytes().all(|c| c.is_ascii_digit()) {
+            symbol = name;
+        }
+    }
+
     Ok((input, symbol))
 }
 
@@ -147,9 +207,45 @@ fn array_assign(input: &str) -> nom::IResult<&str, AssignmentKind> {
     combinator::map(
         sequence::tuple((array_index, binary_string)),
         |(idx, value)| AssignmentKind::Array {
-            index: idx.parse().expect("binary_string parses only 0s and 1s."),
+            index: u64::from_str_radix(idx, 2).expect("binary_string parses only 0s and 1s."),
+            index_bits: idx.len(),
             value: u64::from_str_radix(value, 2).expect("binary_string parses only 0s and 1s."),
             bits: value.len(),
         },
     )(input)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BITVEC: AssignmentKind = AssignmentKind::BitVec {
+        value: 0b0000_0110,
+        bits: 8,
+    };
+
+    const ARRAY: AssignmentKind = AssignmentKind::Array {
+        index: 0b0001,
+        index_bits: 4,
+        value: 0b0000_0101,
+        bits: 8,
+    };
+
+    #[test]
+    fn msb_first_binary_strings() {
+        assert_eq!(BITVEC.to_ordered_binary_string(BitOrder::Msb), "00000110");
+        assert_eq!(
+            ARRAY.to_ordered_binary_string(BitOrder::Msb),
+            "[0001] -> 00000101"
+        );
+    }
+
+    #[test]
+    fn lsb_first_binary_strings() {
+        assert_eq!(BITVEC.to_ordered_binary_string(BitOrder::Lsb), "01100000");
+        assert_eq!(
+            ARRAY.to_ordered_binary_string(BitOrder::Lsb),
+            "[1000] -> 10100000"
+        );
+    }
+}
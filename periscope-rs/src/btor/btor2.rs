@@ -1,5 +1,5 @@
 use std::{
-    collections::HashMap,
+    collections::{hash_map::Entry, HashMap, HashSet},
     io::{BufRead, BufReader, Read},
 };
 
@@ -9,9 +9,36 @@ use crate::btor::witness_format::PropKind;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Property {
+    /// Node id of the property in the BTOR2 model. Node ids start at 1, so 0 means that the node
+    /// is unknown, e.g. if the name is taken from a names file.
     pub node: usize,
     pub _kind: PropKind,
     pub name: Option<String>,
+    /// Line of the BTOR2 model that declares the property, without surrounding whitespace.
+    #[serde(default)]
+    pub source: String,
+}
+
+/// `output` node declared in the BTOR2 model. Outputs are not properties and are not part of the
+/// witness header.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Output {
+    /// Node id of the output in the BTOR2 model.
+    pub node: usize,
+    pub name: Option<String>,
+    /// Symbol of the input or state that is output, `None` if the output is an expression.
+    pub signal: Option<String>,
+    /// Line of the BTOR2 model that declares the output, without surrounding whitespace.
+    pub source: String,
+}
+
+/// Bad and justice properties declared in the BTOR2 model, ordered by their index in the witness
+/// header.
+pub fn get_properties<R: Read>(input: R) -> Vec<(u64, Property)> {
+    let mut properties: Vec<_> = get_property_names(input).into_iter().collect();
+    properties.sort_by_key(|(idx, _)| *idx);
+
+    properties
 }
 
 pub(super) fn get_property_names<R: Read>(input: R) -> HashMap<u64, Property> {
@@ -20,7 +47,7 @@ pub(super) fn get_property_names<R: Read>(input: R) -> HashMap<u64, Property> {
         .lines()
         .filter(|line| match line {
             Ok(line) => line
-                .split(' ')
+                .split_whitespace()
                 .nth(1)
                 .is_some_and(|kind| kind == "bad" || kind == "justice"),
             Err(_) => false,
@@ -28,7 +55,7 @@ pub(super) fn get_property_names<R: Read>(input: R) -> HashMap<u64, Property> {
         .enumerate()
         .filter_map(|(idx, line)| {
             let line = line.ok()?;
-            let mut iter = line.split(' ');
+            let mut iter = line.split_whitespace();
             let node = iter.next()?.parse().ok()?;
             let kind: PropKind = iter.next()?.parse().ok()?;
             let name = iter.nth(1).map(String::from);
@@ -40,8 +67,410 @@ pub(super) fn get_property_names<R: Read>(input: R) -> HashMap<u64, Property> {
                     node,
                     _kind: kind,
                     name,
+                    source: line.trim().to_string(),
                 },
             ))
         })
         .collect()
 }
+
+/// Parses a names file that maps property indices to names, either as lines of '<index> <name>'
+/// or as a JSON object such as '{"0": "name"}'. Empty lines and lines starting with ';' or '#'
+/// are skipped.
+pub(super) fn parse_property_names(input: &str) -> Result<HashMap<u64, String>, String> {
+    if input.trim_start().starts_with('{') {
+        let names: HashMap<String, String> =
+            serde_json::from_str(input).map_err(|err| err.to_string())?;
+
+        return names
+            .into_iter()
+            .map(|(idx, name)| match idx.trim().parse() {
+                Ok(idx) => Ok((idx, name)),
+                Err(_) => Err(format!("'{idx}' is not a property index")),
+            })
+            .collect();
+    }
+
+    let mut names = HashMap::new();
+
+    for (line_idx, line) in input.lines().enumerate() {
+        let line = line.trim();
+
+        if line.is_empty() || line.starts_with(';') || line.starts_with('#') {
+            continue;
+        }
+
+        let parsed = line
+            .split_once(char::is_whitespace)
+            .and_then(|(idx, name)| Some((idx.parse().ok()?, name.trim())));
+
+        match parsed {
+            Some((idx, name)) if !name.is_empty() => {
+                names.insert(idx, name.to_string());
+            }
+            _ => {
+                return Err(format!(
+                    "expected '<index> <name>' in line {}, got '{line}'",
+                    line_idx + 1
+                ))
+            }
+        }
+    }
+
+    Ok(names)
+}
+
+/// Declared size of an array, i.e. an array with `2^index_bits` elements of `element_bits` bits.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ArraySort {
+    pub index_bits: usize,
+    pub element_bits: usize,
+}
+
+impl std::fmt::Display for ArraySort {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "[2^{} x {}]", self.index_bits, self.element_bits)
+    }
+}
+
+/// Collects the declared array sorts of `state` and `input` nodes by their symbol. Nodes without
+/// a symbol and nodes of bitvector sort are skipped.
+pub fn get_array_sorts<R: Read>(input: R) -> HashMap<String, ArraySort> {
+    let mut bitvecs: HashMap<&str, usize> = HashMap::new();
+    let mut arrays: HashMap<&str, ArraySort> = HashMap::new();
+    let mut signals = HashMap::new();
+
+    let lines: Vec<String> = BufReader::new(input)
+        .lines()
+        .map_while(Result::ok)
+        .collect();
+
+    for line in &lines {
+        let mut iter = line.split_whitespace();
+        let (Some(node), Some(kind)) = (iter.next(), iter.next()) else {
+            continue;
+        };
+
+        match (kind, iter.next(), iter.next(), iter.next()) {
+            ("sort", Some("bitvec"), Some(width), _) => {
+                if let Ok(width) = width.parse() {
+                    bitvecs.insert(node, width);
+                }
+            }
+            ("sort", Some("array"), Some(index), Some(element)) => {
+                if let (Some(index_bits), Some(element_bits)) =
+                    (bitvecs.get(index), bitvecs.get(element))
+                {
+                    arrays.insert(
+                        node,
+                        ArraySort {
+                            index_bits: *index_bits,
+                            element_bits: *element_bits,
+                        },
+                    );
+                }
+            }
+            ("state" | "input", Some(sort), Some(symbol), _) => {
+                if let Some(array) = arrays.get(sort) {
+                    signals.insert(symbol.to_string(), *array);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    signals
+}
+
+/// Collects the symbols of all `state` and `input` nodes declared in the BTOR2 model.
+pub fn get_declared_signals<R: Read>(input: R) -> HashSet<String> {
+    BufReader::new(input)
+        .lines()
+        .map_while(Result::ok)
+        .filter_map(|line| {
+            let mut iter = line.split_whitespace();
+            let kind = iter.nth(1)?;
+
+            if kind != "state" && kind != "input" {
+                return None;
+            }
+
+            iter.nth(1).map(String::from)
+        })
+        .collect()
+}
+
+/// Maps the node ids of the `state` and `input` nodes declared in the BTOR2 model to their
+/// symbols. Nodes without a symbol are skipped.
+pub(super) fn get_signal_nodes<R: Read>(input: R) -> HashMap<usize, String> {
+    BufReader::new(input)
+        .lines()
+        .map_while(Result::ok)
+        .filter_map(|line| {
+            let mut iter = line.split_whitespace();
+            let node = iter.next()?.parse().ok()?;
+            let kind = iter.next()?;
+
+            if kind != "state" && kind != "input" {
+                return None;
+            }
+
+            iter.nth(1).map(|symbol| (node, String::from(symbol)))
+        })
+        .collect()
+}
+
+/// Collects the symbols of the `state` and `input` nodes that the given nodes depend on, directly
+/// or through the initial and next values of states, i.e. the cone of influence of the nodes.
+pub(super) fn cone_of_influence<R: Read>(input: R, roots: &[usize]) -> HashSet<String> {
+    let mut dependencies: HashMap<usize, Vec<usize>> = HashMap::new();
+    let mut signals = HashMap::new();
+
+    for line in BufReader::new(input).lines().map_while(Result::ok) {
+        let tokens: Vec<_> = line.split_whitespace().collect();
+        let (Some(Ok(node)), Some(&op)) = (tokens.first().map(|nid| nid.parse()), tokens.get(1))
+        else {
+            continue;
+        };
+
+        // arguments are given as '<nid> <op> <sid> <args...>', except for the nodes without sort
+        let (node, first_arg, arity) = match op {
+            "state" | "input" => {
+                if let Some(symbol) = tokens.get(3) {
+                    signals.insert(node, symbol.to_string());
+                }
+                continue;
+            }
+            "sort" | "const" | "constd" | "consth" | "zero" | "one" | "ones" => continue,
+            // the initial and next value are dependencies of the state
+            "init" | "next" => match tokens.get(3).and_then(|state| state.parse().ok()) {
+                Some(state) => (state, 4, 1),
+                None => continue,
+            },
+            "bad" | "constraint" | "fair" | "output" => (node, 2, 1),
+            "justice" => (node, 3, usize::MAX),
+            "not" | "inc" | "dec" | "neg" | "redand" | "redor" | "redxor" | "slice" | "uext"
+            | "sext" => (node, 3, 1),
+            "ite" | "write" => (node, 3, 3),
+            _ => (node, 3, 2),
+        };
+
+        dependencies.entry(node).or_default().extend(
+            tokens
+                .iter()
+                .skip(first_arg)
+                .take(arity)
+                .filter_map(|arg| arg.parse::<i64>().ok())
+                // negated arguments refer to the same node
+                .map(|arg| arg.unsigned_abs() as usize),
+        );
+    }
+
+    let mut visited = HashSet::new();
+    let mut pending = roots.to_vec();
+
+    while let Some(node) = pending.pop() {
+        if !visited.insert(node) {
+            continue;
+        }
+
+        if let Some(args) = dependencies.get(&node) {
+            pending.extend(args);
+        }
+    }
+
+    visited
+        .iter()
+        .filter_map(|node| signals.get(node).cloned())
+        .collect()
+}
+
+/// Collects the `output` nodes declared in the BTOR2 model, in the order of their declaration.
+pub fn get_outputs<R: Read>(mut input: R) -> Vec<Output> {
+    let mut model = String::new();
+    if input.read_to_string(&mut model).is_err() {
+        return Vec::new();
+    }
+
+    let signals = get_signal_nodes(model.as_bytes());
+
+    model
+        .lines()
+        .filter_map(|line| {
+            // outputs are declared as '<nid> output <node> [symbol]'
+            let mut iter = line.split_whitespace();
+            let node = iter.next()?.parse().ok()?;
+
+            if iter.next()? != "output" {
+                return None;
+            }
+
+            let arg: i64 = iter.next()?.parse().ok()?;
+
+            Some(Output {
+                node,
+                name: iter.next().map(String::from),
+                // negated signals are expressions as well
+                signal: usize::try_from(arg)
+                    .ok()
+                    .and_then(|arg| signals.get(&arg).cloned()),
+                source: line.trim().to_string(),
+            })
+        })
+        .collect()
+}
+
+/// Number of declarations of each kind in a BTOR2 model.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct DeclarationCounts {
+    pub bad: usize,
+    pub justice: usize,
+    pub constraint: usize,
+    pub state: usize,
+    pub input: usize,
+}
+
+/// Counts the `bad`, `justice`, `constraint`, `state` and `input` declarations of the BTOR2 model.
+pub fn count_declarations<R: Read>(input: R) -> DeclarationCounts {
+    let mut counts = DeclarationCounts::default();
+
+    for line in BufReader::new(input).lines().map_while(Result::ok) {
+        let count = match line.split_whitespace().nth(1) {
+            Some("bad") => &mut counts.bad,
+            Some("justice") => &mut counts.justice,
+            Some("constraint") => &mut counts.constraint,
+            Some("state") => &mut counts.state,
+            Some("input") => &mut counts.input,
+            _ => continue,
+        };
+
+        *count += 1;
+    }
+
+    counts
+}
+
+/// Problem with the node ids of a BTOR2 model.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Btor2Problem {
+    /// Node id on the given line is not a number.
+    MalformedNode { line: usize, node: String },
+
+    /// Node id is declared on more than one line.
+    DuplicateNode {
+        node: u64,
+        first_line: usize,
+        line: usize,
+    },
+
+    /// Property of a kind that periscope can't report, e.g. `fair`.
+    UnsupportedProperty { line: usize, kind: String },
+}
+
+impl std::fmt::Display for Btor2Problem {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Btor2Problem::MalformedNode { line, node } => {
+                write!(f, "line {line}: node id '{node}' is not a number")
+            }
+            Btor2Problem::DuplicateNode {
+                node,
+                first_line,
+                line,
+            } => write!(
+                f,
+                "line {line}: node id {node} was already declared on line {first_line}"
+            ),
+            Btor2Problem::UnsupportedProperty { line, kind } => {
+                write!(f, "line {line}: property kind '{kind}' is not supported")
+            }
+        }
+    }
+}
+
+/// Finds properties of the BTOR2 model that are neither bad properties, justice properties nor
+/// constraints. Such properties would be missing from the report. Line numbers in the reported
+/// problems start at 1.
+pub fn check_property_kinds<R: Read>(input: R) -> Vec<Btor2Problem> {
+    BufReader::new(input)
+        .lines()
+        .map_while(Result::ok)
+        .enumerate()
+        .filter_map(|(idx, line)| {
+            let kind = line.split_whitespace().nth(1)?;
+
+            (kind == "fair").then(|| Btor2Problem::UnsupportedProperty {
+                line: idx + 1,
+                kind: kind.to_string(),
+            })
+        })
+        .collect()
+}
+
+/// Checks that every node in the BTOR2 model has a numeric and unique node id. Line numbers in the
+/// reported problems start at 1.
+pub fn check_node_ids<R: Read>(input: R) -> Vec<Btor2Problem> {
+    let mut problems = Vec::new();
+    let mut declared: HashMap<u64, usize> = HashMap::new();
+
+    let lines = BufReader::new(input).lines().map_while(Result::ok);
+
+    for (idx, line) in lines.enumerate() {
+        let line_nr = idx + 1;
+
+        let Some(node) = line.split_whitespace().next() else {
+            continue;
+        };
+
+        if node.starts_with(';') {
+            continue;
+        }
+
+        let Ok(node_id) = node.parse() else {
+            problems.push(Btor2Problem::MalformedNode {
+                line: line_nr,
+                node: node.to_string(),
+            });
+            continue;
+        };
+
+        match declared.entry(node_id) {
+            Entry::Occupied(first) => problems.push(Btor2Problem::DuplicateNode {
+                node: node_id,
+                first_line: *first.get(),
+                line: line_nr,
+            }),
+            Entry::Vacant(entry) => {
+                entry.insert(line_nr);
+            }
+        }
+    }
+
+    problems
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tab_separated_properties_are_parsed() {
+        let model = "43010 one 1\n43011\tbad\t43010\tcore-0-illegal-instruction\n";
+        let properties = get_property_names(model.as_bytes());
+
+        let property = &properties[&0];
+        assert_eq!(property.node, 43011);
+        assert_eq!(property._kind, PropKind::Bad);
+        assert_eq!(property.name.as_deref(), Some("core-0-illegal-instruction"));
+    }
+
+    #[test]
+    fn properties_separated_by_several_spaces_are_parsed() {
+        let model = "2  bad   1    first\n3 bad 1 second\n";
+        let properties = get_property_names(model.as_bytes());
+
+        assert_eq!(properties[&0].node, 2);
+        assert_eq!(properties[&0].name.as_deref(), Some("first"));
+        assert_eq!(properties[&1].node, 3);
+    }
+}
@@ -0,0 +1,28 @@
+use std::io::Write;
+
+use super::flow::Flow;
+
+/// Writes the flow in the collapsed stack format of flamegraph tools. Each value of a signal is
+/// a line '<flow type>;<signal>;<step>=<value> <steps>', weighted by the number of steps the
+/// signal kept the value, up to and including the last step of its witness block.
+pub(crate) fn write_flow(out: &mut dyn Write, flow: &Flow) -> std::io::Result<()> {
+    let flow_type = flow.flow_type();
+
+    for (name, _) in flow.signals() {
+        for (step, assignment, steps) in flow.held_steps(name) {
+            writeln!(
+                out,
+                "{};{};{}{}={} {}",
+                flow_type.name(),
+                // ';' separates the frames of a stack
+                name.replace(';', "_"),
+                flow_type.prefix(),
+                step,
+                assignment.get_value(),
+                steps
+            )?;
+        }
+    }
+
+    Ok(())
+}
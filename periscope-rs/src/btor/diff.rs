@@ -0,0 +1,181 @@
+use std::{collections::BTreeSet, io::Write};
+
+use crate::error::Result;
+
+use super::{
+    assignment::Assignment,
+    flow::{Flow, FlowType},
+    ReportOptions, Witness,
+};
+
+/// First step at which a signal has different values in two witnesses.
+#[derive(Debug, Clone)]
+pub struct SignalDiff {
+    pub flow_type: FlowType,
+    pub name: String,
+    pub step: u64,
+    /// Value in the first witness, `None` if the signal is not assigned yet at `step`.
+    pub first: Option<Assignment>,
+    /// Value in the second witness, `None` if the signal is not assigned yet at `step`.
+    pub second: Option<Assignment>,
+}
+
+/// Differences between the signal flows of two witnesses, compared step by step.
+#[derive(Debug, Clone, Default)]
+pub struct WitnessDiff {
+    /// Largest step of the first and second witness.
+    pub max_steps: (u64, u64),
+    /// Signals of both witnesses whose values differ at some step, ordered by that step.
+    pub differing: Vec<SignalDiff>,
+    /// Number of signals present in both witnesses.
+    pub common: usize,
+    /// Signals that are only present in the first witness.
+    pub only_in_first: Vec<(FlowType, String)>,
+    /// Signals that are only present in the second witness.
+    pub only_in_second: Vec<(FlowType, String)>,
+}
+
+impl Witness {
+    /// Compares the input and state flows of this witness with the `other` witness. For each
+    /// signal present in both, the first step at which their values differ is reported.
+    pub fn diff(&self, other: &Witness) -> Result<WitnessDiff> {
+        let options = ReportOptions::default();
+        let mut diff = WitnessDiff::default();
+
+        let flows = [
+            (
+                self.analyze_input_flow(&options)?,
+                other.analyze_input_flow(&options)?,
+            ),
+            (
+                self.analyze_state_flow(&options)?,
+                other.analyze_state_flow(&options)?,
+            ),
+        ];
+
+        for (first, second) in &flows {
+            diff.max_steps = (
+                diff.max_steps.0.max(first.max_step()),
+                diff.max_steps.1.max(second.max_step()),
+            );
+
+            for (name, assignments) in first.signals() {
+                let Some(other_assignments) = second.get(name) else {
+                    diff.only_in_first
+                        .push((first.flow_type(), name.to_string()));
+                    continue;
+                };
+
+                diff.common += 1;
+
+                let steps: BTreeSet<u64> = assignments
+                    .iter()
+                    .chain(other_assignments)
+                    .map(|(step, _)| *step)
+                    .collect();
+
+                if let Some(step) = steps
+                    .into_iter()
+                    .find(|step| !same_value(first, second, name, *step))
+                {
+                    diff.differing.push(SignalDiff {
+                        flow_type: first.flow_type(),
+                        name: name.to_string(),
+                        step,
+                        first: first.value_at(name, step).cloned(),
+                        second: second.value_at(name, step).cloned(),
+                    });
+                }
+            }
+
+            for (name, _) in second.signals() {
+                if first.get(name).is_none() {
+                    diff.only_in_second
+                        .push((second.flow_type(), name.to_string()));
+                }
+            }
+        }
+
+        diff.differing.sort_by_key(|signal| signal.step);
+
+        Ok(diff)
+    }
+}
+
+fn same_value(first: &Flow, second: &Flow, name: &str, step: u64) -> bool {
+    match (first.value_at(name, step), second.value_at(name, step)) {
+        (Some(first), Some(second)) => first.kind == second.kind,
+        (None, None) => true,
+        _ => false,
+    }
+}
+
+impl WitnessDiff {
+    /// Writes a human readable report of the differences.
+    pub fn write_report(&self, out: &mut dyn Write) -> std::io::Result<()> {
+        let (first_steps, second_steps) = self.max_steps;
+
+        if first_steps != second_steps {
+            writeln!(
+                out,
+                "Witnesses end at different steps: {first_steps} and {second_steps}.\n"
+            )?;
+        }
+
+        if self.differing.is_empty() {
+            writeln!(
+                out,
+                "All {} common signals have the same values.",
+                self.common
+            )?;
+        } else {
+            writeln!(
+                out,
+                "{} of {} common signals differ:",
+                self.differing.len(),
+                self.common
+            )?;
+
+            for signal in &self.differing {
+                writeln!(
+                    out,
+                    "    {} {} first differs at {}{}: {} vs {}",
+                    signal.flow_type.name(),
+                    signal.name,
+                    signal.flow_type.prefix(),
+                    signal.step,
+                    describe(signal.first.as_ref()),
+                    describe(signal.second.as_ref())
+                )?;
+            }
+        }
+
+        for (label, signals) in [
+            ("first", &self.only_in_first),
+            ("second", &self.only_in_second),
+        ] {
+            if signals.is_empty() {
+                continue;
+            }
+
+            writeln!(out, "\nOnly in the {label} witness:")?;
+
+            for (flow_type, name) in signals {
+                writeln!(out, "    {} {}", flow_type.name(), name)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn describe(assignment: Option<&Assignment>) -> String {
+    match assignment {
+        Some(assignment) => format!(
+            "{} ({})",
+            assignment.get_value(),
+            assignment.kind.to_binary_string()
+        ),
+        None => String::from("unassigned"),
+    }
+}
@@ -0,0 +1,78 @@
+use std::collections::BTreeSet;
+
+use super::flow::Flow;
+
+/// Differences between the expected flows, e.g. a golden file written with `--format json`, and
+/// the flows of the witness. Each difference is described in one line, the flows match if there
+/// are none.
+pub(crate) fn compare(expected: &[Flow], actual: &[Flow]) -> Vec<String> {
+    let mut mismatches = Vec::new();
+
+    for expected_flow in expected {
+        let flow_type = expected_flow.flow_type();
+
+        let Some(actual_flow) = actual.iter().find(|flow| flow.flow_type() == flow_type) else {
+            mismatches.push(format!("{} flow is missing", flow_type.name()));
+            continue;
+        };
+
+        let names: BTreeSet<&str> = expected_flow
+            .signals()
+            .chain(actual_flow.signals())
+            .map(|(name, _)| name)
+            .collect();
+
+        for name in names {
+            let (Some(expected), Some(actual)) = (expected_flow.get(name), actual_flow.get(name))
+            else {
+                let problem = match expected_flow.get(name) {
+                    Some(_) => "is missing",
+                    None => "is not expected",
+                };
+
+                mismatches.push(format!("{} {name} {problem}", flow_type.name()));
+                continue;
+            };
+
+            let differs =
+                expected
+                    .iter()
+                    .zip(actual)
+                    .find(|((step, value), (other_step, other))| {
+                        step != other_step || value.kind != other.kind
+                    });
+
+            if let Some(((step, value), (other_step, other))) = differs {
+                mismatches.push(format!(
+                    "{} {name}: expected {} at {}{step}, got {} at {}{other_step}",
+                    flow_type.name(),
+                    value.get_value(),
+                    flow_type.prefix(),
+                    other.get_value(),
+                    flow_type.prefix(),
+                ));
+            } else if expected.len() != actual.len() {
+                mismatches.push(format!(
+                    "{} {name}: expected {} changes, got {}",
+                    flow_type.name(),
+                    expected.len(),
+                    actual.len()
+                ));
+            }
+        }
+    }
+
+    for actual_flow in actual {
+        if !expected
+            .iter()
+            .any(|flow| flow.flow_type() == actual_flow.flow_type())
+        {
+            mismatches.push(format!(
+                "{} flow is not expected",
+                actual_flow.flow_type().name()
+            ));
+        }
+    }
+
+    mismatches
+}
@@ -0,0 +1,118 @@
+use nom::{combinator, multi};
+
+use crate::error::ParseError;
+
+use super::{
+    assignment::{Assignment, AssignmentKind},
+    witness_format::Prop,
+    PropTags,
+};
+
+/// Explains a single line of a witness, e.g. '5 00000110 x@3' is an 8-bit input assignment of
+/// `x` at step 3. Assignments, frame headers, property lists of the witness header and the
+/// keywords 'sat' and '.' are recognized.
+pub fn explain_line(line: &str, tags: &PropTags) -> Result<String, ParseError> {
+    let line = line.trim();
+
+    match line {
+        "sat" => {
+            return Ok(String::from(
+                "start of a witness, a counterexample was found",
+            ))
+        }
+        "unsat" => return Ok(String::from("no counterexample was found")),
+        "." => return Ok(String::from("end of a witness")),
+        _ => {}
+    }
+
+    if let Some(step) = frame_step(line, '#') {
+        return Ok(format!("start of the state assignments at step {step}"));
+    }
+
+    if let Some(step) = frame_step(line, '@') {
+        return Ok(format!("start of the input assignments at step {step}"));
+    }
+
+    // properties are written without separators, e.g. 'b0j1'
+    let compact: String = line.split_whitespace().collect();
+    let props =
+        combinator::all_consuming(multi::many1(|input| Prop::parse(tags, input)))(compact.as_str());
+
+    if let Ok((_, props)) = props {
+        let props: Vec<_> = props.iter().map(Prop::to_string).collect();
+        return Ok(format!("satisfied properties: {}", props.join(", ")));
+    }
+
+    // the assignment parser expects a whole line
+    let input = format!("{line}\n");
+    let (_, assignment) = Assignment::parse(&input)
+        .map_err(|err| match err {
+            nom::Err::Error(err) | nom::Err::Failure(err) => {
+                ParseError::at(&input, err.input, Some(err.code))
+            }
+            nom::Err::Incomplete(_) => ParseError::input("Incomplete line."),
+        })
+        .map_err(|mut err| {
+            err.message = format!("{}, not an assignment, frame or property", err.message);
+            err
+        })?;
+
+    let mut parts = Vec::new();
+
+    match &assignment.symbol {
+        Some(symbol) => parts.push(format!("signal {symbol}")),
+        None => parts.push(String::from("unnamed signal")),
+    }
+
+    if let Some((kind, step)) = symbol_step(line) {
+        parts.push(format!("{kind} at step {step}"));
+    }
+
+    if let Some(position) = line.split_whitespace().next() {
+        parts.push(format!("position {position}"));
+    }
+
+    match assignment.kind {
+        AssignmentKind::BitVec { value, bits } => {
+            parts.push(format!("{bits}-bit bitvector"));
+            parts.push(format!("decimal {value}"));
+            parts.push(format!("hex {}", hex(value, bits)));
+        }
+        AssignmentKind::Array {
+            index,
+            index_bits,
+            value,
+            bits,
+        } => {
+            parts.push(format!(
+                "array with {index_bits}-bit indices and {bits}-bit values"
+            ));
+            parts.push(format!("index {index} ({})", hex(index, index_bits)));
+            parts.push(format!("value {value} ({})", hex(value, bits)));
+        }
+    }
+
+    Ok(parts.join(", "))
+}
+
+/// Step of a frame header such as '#3' or '@3'.
+fn frame_step(line: &str, prefix: char) -> Option<u64> {
+    line.strip_prefix(prefix)?.parse().ok()
+}
+
+/// Whether the symbol of the assignment marks an input ('x@3') or a state ('x#3') and at which
+/// step. Symbols without such a suffix return `None`.
+fn symbol_step(line: &str) -> Option<(&'static str, u64)> {
+    let symbol = line.split_whitespace().last()?;
+
+    [('@', "input"), ('#', "state")]
+        .into_iter()
+        .find_map(|(separator, kind)| {
+            let (_, step) = symbol.rsplit_once(separator)?;
+            Some((kind, step.parse().ok()?))
+        })
+}
+
+fn hex(value: u64, bits: usize) -> String {
+    format!("0x{:0w$x}", value, w = bits.div_ceil(4))
+}
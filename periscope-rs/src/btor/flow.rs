@@ -0,0 +1,158 @@
+use std::collections::{BTreeMap, HashMap};
+
+use serde::{Deserialize, Serialize};
+
+use super::{assignment::Assignment, btor2::ArraySort};
+
+/// Whether a flow consists of inputs or states.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FlowType {
+    State,
+    Input,
+}
+
+impl FlowType {
+    pub(crate) fn name(self) -> &'static str {
+        match self {
+            FlowType::State => "state",
+            FlowType::Input => "input",
+        }
+    }
+
+    /// Prefix used for the steps of this flow type in the witness format.
+    pub(crate) fn prefix(self) -> &'static str {
+        match self {
+            FlowType::State => "#",
+            FlowType::Input => "@",
+        }
+    }
+}
+
+/// Assignments of inputs or states grouped by signal name. Each assignment is stored together
+/// with the step at which it was assigned. Consecutive assignments of the same value are only
+/// stored once.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Flow {
+    flow_type: FlowType,
+    signals: BTreeMap<String, Vec<(u64, Assignment)>>,
+    max_step: u64,
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    array_sorts: HashMap<String, ArraySort>,
+    /// For witnesses with more than one block, the position of the first assignment of each
+    /// signal in every block, together with the last step of that block. Steps restart in each
+    /// block, so values are only held until the end of their block.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    blocks: BTreeMap<String, Vec<(usize, u64)>>,
+}
+
+impl Flow {
+    pub(crate) fn new(
+        flow_type: FlowType,
+        signals: BTreeMap<String, Vec<(u64, Assignment)>>,
+        max_step: u64,
+        array_sorts: HashMap<String, ArraySort>,
+        blocks: BTreeMap<String, Vec<(usize, u64)>>,
+    ) -> Self {
+        Self {
+            flow_type,
+            signals,
+            max_step,
+            array_sorts,
+            blocks,
+        }
+    }
+
+    pub fn flow_type(&self) -> FlowType {
+        self.flow_type
+    }
+
+    /// Signals ordered by name, together with their assignments.
+    pub fn signals(&self) -> impl Iterator<Item = (&str, &[(u64, Assignment)])> {
+        self.signals
+            .iter()
+            .map(|(name, flow)| (name.as_str(), flow.as_slice()))
+    }
+
+    /// Assignments of the signal with the given name.
+    pub fn get(&self, name: &str) -> Option<&[(u64, Assignment)]> {
+        self.signals.get(name).map(Vec::as_slice)
+    }
+
+    /// Value of the signal at the given step, i.e. its latest assignment at or before `step`.
+    pub fn value_at(&self, name: &str, step: u64) -> Option<&Assignment> {
+        let assignments = self.signals.get(name)?;
+        let idx = assignments.partition_point(|(assigned, _)| *assigned <= step);
+
+        idx.checked_sub(1).map(|idx| &assignments[idx].1)
+    }
+
+    /// Declared sort of the array signal with the given name, known if the BTOR2 model was
+    /// provided.
+    pub fn array_sort(&self, name: &str) -> Option<ArraySort> {
+        self.array_sorts.get(name).copied()
+    }
+
+    /// Largest step of the witness, which is where the flow ends.
+    pub fn max_step(&self) -> u64 {
+        self.max_step
+    }
+
+    /// Step and value of each of the assignments of the signal together with the number of steps
+    /// the signal kept the value, up to and including the last step of its block.
+    pub(crate) fn held_steps<'a>(
+        &'a self,
+        name: &str,
+    ) -> impl Iterator<Item = (u64, &'a Assignment, u64)> + 'a {
+        self.intervals(name)
+            .map(|(step, assignment, next, block_end)| {
+                let end = next.unwrap_or(block_end + 1);
+                (step, assignment, end.saturating_sub(step))
+            })
+    }
+
+    /// Step and value of each of the assignments of the signal, the step of the next assignment
+    /// in the same block, if any, and the last step of the block.
+    pub(crate) fn intervals<'a>(
+        &'a self,
+        name: &str,
+    ) -> impl Iterator<Item = (u64, &'a Assignment, Option<u64>, u64)> + 'a {
+        let assignments = self.get(name).unwrap_or_default();
+        let blocks = self.blocks.get(name).map(Vec::as_slice).unwrap_or_default();
+
+        assignments
+            .iter()
+            .enumerate()
+            .map(move |(pos, (step, assignment))| {
+                // blocks starting at or before the assignment, the last of them contains it
+                let started = blocks.partition_point(|(start, _)| *start <= pos);
+                let block_end = match started {
+                    0 => self.max_step,
+                    _ => blocks[started - 1].1,
+                };
+                let next_block = blocks
+                    .get(started)
+                    .map_or(assignments.len(), |(start, _)| *start);
+                let next = (pos + 1 < next_block).then(|| assignments[pos + 1].0);
+
+                (*step, assignment, next, block_end)
+            })
+    }
+
+    /// Keeps only the signals for which `keep` returns true.
+    pub(crate) fn retain(&mut self, mut keep: impl FnMut(&str, &[(u64, Assignment)]) -> bool) {
+        self.signals
+            .retain(|name, assignments| keep(name, assignments));
+        self.blocks
+            .retain(|name, _| self.signals.contains_key(name));
+    }
+
+    /// Number of signals in the flow.
+    pub fn len(&self) -> usize {
+        self.signals.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.signals.is_empty()
+    }
+}
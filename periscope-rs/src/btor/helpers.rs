@@ -12,3 +12,57 @@ pub fn comment(input: &str) -> nom::IResult<&str, ()> {
     let first = sequence::preceded(complete::tag(";"), complete::take_until("\\n"));
     combinator::map(sequence::terminated(first, newline), |_| ())(input)
 }
+
+/// Shortens `digits` to at most `max` digits by replacing the middle with '...', keeping the
+/// most and least significant digits.
+pub fn elide_middle(digits: &str, max: usize) -> String {
+    if digits.len() <= max {
+        return digits.to_string();
+    }
+
+    let low = max / 2;
+    let high = max - low;
+
+    format!("{}...{}", &digits[..high], &digits[digits.len() - low..])
+}
+
+/// Formats `value` as an unsigned fixed-point number with the binary point `frac_bits` bits from
+/// the right, e.g. 6 with 2 fractional bits is '1.5'. At least one fractional digit is written.
+pub fn fixed_point(value: u64, frac_bits: u32) -> String {
+    let integer = value.checked_shr(frac_bits).unwrap_or(0);
+    let mut fraction = u128::from(value) & ((1u128 << frac_bits) - 1);
+    let mut digits = String::new();
+
+    // every fractional binary digit adds one decimal digit, so this terminates
+    while fraction != 0 {
+        fraction *= 10;
+        digits.push(char::from(b'0' + (fraction >> frac_bits) as u8));
+        fraction &= (1u128 << frac_bits) - 1;
+    }
+
+    if digits.is_empty() {
+        digits.push('0');
+    }
+
+    format!("{integer}.{digits}")
+}
+
+/// Number of digits needed to print `value` in decimal. Zero needs one digit as well.
+pub fn decimal_width(value: u64) -> usize {
+    value
+        .checked_ilog10()
+        .map_or(1, |digits| digits as usize + 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decimal_width_of_zero_is_one() {
+        assert_eq!(decimal_width(0), 1);
+        assert_eq!(decimal_width(9), 1);
+        assert_eq!(decimal_width(10), 2);
+        assert_eq!(decimal_width(u64::MAX), 20);
+    }
+}
@@ -0,0 +1,143 @@
+use std::{
+    ffi::OsStr,
+    fs::File,
+    io::Read,
+    net::TcpListener,
+    path::Path,
+    process::{Child, ChildStdout, Command, Stdio},
+};
+
+use crate::{
+    error::{PeriscopeError, Result},
+    output,
+};
+
+/// Opens the file at `path` for reading. Files with `.gz`, `.bz2` or `.zst` extension are
+/// decompressed on the fly using the `gzip`, `bzip2` or `zstd` command respectively.
+pub fn open_maybe_compressed(path: impl AsRef<Path>) -> Result<Box<dyn Read>> {
+    let path = path.as_ref();
+
+    let decompressor = match path.extension().and_then(OsStr::to_str) {
+        Some("gz") => "gzip",
+        Some("bz2") => "bzip2",
+        Some("zst") => "zstd",
+        _ => {
+            let file = File::open(path).map_err(PeriscopeError::file(path))?;
+
+            return Ok(Box::new(file));
+        }
+    };
+
+    let mut child = Command::new(decompressor)
+        .arg("-dc")
+        .arg(path)
+        .stdout(Stdio::piped())
+        .spawn()
+        .map_err(|source| PeriscopeError::Tool {
+            tool: decompressor.to_string(),
+            source,
+        })?;
+
+    let stdout = child.stdout.take().expect("Decompressor output is piped.");
+
+    Ok(Box::new(Decompressed {
+        name: decompressor,
+        child,
+        stdout,
+    }))
+}
+
+/// Output of a running decompressor process.
+struct Decompressed {
+    name: &'static str,
+    child: Child,
+    stdout: ChildStdout,
+}
+
+impl Read for Decompressed {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let read = self.stdout.read(buf)?;
+
+        if read == 0 && !buf.is_empty() {
+            let status = self.child.wait()?;
+
+            if !status.success() {
+                return Err(std::io::Error::other(format!(
+                    "'{}' failed to decompress input: {status}",
+                    self.name
+                )));
+            }
+        }
+
+        Ok(read)
+    }
+}
+
+impl Drop for Decompressed {
+    /// Stops the decompressor if the input was not read to the end, e.g. because parsing failed,
+    /// and reaps the process so that it does not linger as a zombie.
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+/// Splits the input into independent witnesses separated by lines that contain only `marker`.
+/// Segments that contain nothing but whitespace are skipped.
+pub fn split_witnesses<'a>(input: &'a str, marker: &str) -> Vec<&'a str> {
+    let mut segments = Vec::new();
+    let mut start = 0;
+    let mut offset = 0;
+
+    for line in input.split_inclusive('\n') {
+        if line.trim() == marker {
+            segments.push(&input[start..offset]);
+            start = offset + line.len();
+        }
+
+        offset += line.len();
+    }
+
+    segments.push(&input[start..]);
+    segments.retain(|segment| !segment.trim().is_empty());
+
+    segments
+}
+
+/// Splits the input at the first line that contains only `marker` into the witness before and the
+/// BTOR2 model after the marker. Returns `None` if there is no such line.
+pub fn split_model<'a>(input: &'a str, marker: &str) -> Option<(&'a str, &'a str)> {
+    let mut offset = 0;
+
+    for line in input.split_inclusive('\n') {
+        if line.trim() == marker {
+            return Some((&input[..offset], &input[offset + line.len()..]));
+        }
+
+        offset += line.len();
+    }
+
+    None
+}
+
+/// Waits for a single TCP connection on `addr` and returns it for reading the witness. The
+/// witness is read until the sender closes the connection.
+pub fn accept_witness(addr: &str) -> Result<Box<dyn Read>> {
+    let listen_error = |source| PeriscopeError::Listen {
+        addr: addr.to_string(),
+        source,
+    };
+
+    let listener = TcpListener::bind(addr).map_err(listen_error)?;
+
+    output::info(format!(
+        "Waiting for a witness on '{}'.",
+        listener
+            .local_addr()
+            .map_or_else(|_| addr.to_string(), |a| a.to_string())
+    ));
+
+    let (stream, _) = listener.accept().map_err(listen_error)?;
+
+    Ok(Box::new(stream))
+}
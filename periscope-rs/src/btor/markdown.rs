@@ -0,0 +1,122 @@
+use std::io::Write;
+
+use super::{
+    assignment::AssignmentKind,
+    flow::{Flow, FlowType},
+    options::Radix,
+    PropVec,
+};
+
+/// Writes the satisfied properties as a Markdown table. The BTOR2 source of the properties is
+/// added as a column if `show_source` is set.
+pub(crate) fn write_props(
+    out: &mut dyn Write,
+    props: &PropVec,
+    steps: usize,
+    show_source: bool,
+) -> std::io::Result<()> {
+    writeln!(out, "## Satisfied properties in {steps} steps\n")?;
+
+    let mut header = vec!["Kind", "Index", "Name", "Node"];
+    if show_source {
+        header.push("Source");
+    }
+
+    write_row(out, header.iter().map(|cell| cell.to_string()))?;
+    write_row(out, header.iter().map(|_| String::from("---")))?;
+
+    for prop in &props.inner {
+        let property = prop.property.as_ref();
+
+        let mut row = vec![
+            format!("{:?}", prop.kind),
+            prop.idx.to_string(),
+            property
+                .and_then(|property| property.name.clone())
+                .unwrap_or_default(),
+            property
+                .filter(|property| property.node != 0)
+                .map(|property| property.node.to_string())
+                .unwrap_or_default(),
+        ];
+
+        if show_source {
+            row.push(
+                property
+                    .map(|property| format!("`{}`", property.source))
+                    .unwrap_or_default(),
+            );
+        }
+
+        write_row(out, row)?;
+    }
+
+    writeln!(out)
+}
+
+/// Writes the flow of each signal as a Markdown table with one row per change of its value.
+pub(crate) fn write_flow(out: &mut dyn Write, flow: &Flow, radix: Radix) -> std::io::Result<()> {
+    match flow.flow_type() {
+        FlowType::Input => writeln!(out, "## Inputs flow\n")?,
+        FlowType::State => writeln!(out, "## States flow\n")?,
+    }
+
+    if flow.is_empty() {
+        return writeln!(out, "No assignments.\n");
+    }
+
+    for (name, assignments) in flow.signals() {
+        match flow.array_sort(name) {
+            Some(sort) => writeln!(out, "### {} {}\n", escape(name), sort)?,
+            None => writeln!(out, "### {}\n", escape(name))?,
+        }
+
+        write_row(out, ["Step", "Value"].map(String::from))?;
+        write_row(out, ["---:", "---:"].map(String::from))?;
+
+        for (step, assignment) in assignments {
+            write_row(
+                out,
+                [
+                    format!("{}{}", flow.flow_type().prefix(), step),
+                    format_value(assignment.kind, radix),
+                ],
+            )?;
+        }
+
+        writeln!(out)?;
+    }
+
+    Ok(())
+}
+
+fn format_value(kind: AssignmentKind, radix: Radix) -> String {
+    match kind {
+        AssignmentKind::BitVec { value, bits } => radix.format(value, bits),
+        AssignmentKind::Array {
+            index,
+            index_bits,
+            value,
+            bits,
+        } => format!(
+            "[{}] -> {}",
+            radix.format(index, index_bits),
+            radix.format(value, bits)
+        ),
+    }
+}
+
+fn write_row(out: &mut dyn Write, cells: impl IntoIterator<Item = String>) -> std::io::Result<()> {
+    write!(out, "|")?;
+
+    for cell in cells {
+        write!(out, " {} |", escape(&cell))?;
+    }
+
+    writeln!(out)
+}
+
+/// Escapes characters that would end a table cell or start a new row.
+fn escape(text: &str) -> String {
+    text.replace('|', "\\|").replace('\n', " ")
+}
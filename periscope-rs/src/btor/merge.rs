@@ -0,0 +1,204 @@
+use std::collections::{hash_map::Entry, HashMap};
+
+use crate::error::{PeriscopeError, Result};
+
+use super::{
+    assignment::AssignmentKind,
+    witness_format::{Model, WitnessFormat},
+    Witness,
+};
+
+/// Signal that was assigned different values at the same step by two of the merged witnesses.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MergeConflict {
+    pub signal: String,
+    pub step: u64,
+    pub first: u64,
+    pub second: u64,
+}
+
+impl std::fmt::Display for MergeConflict {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "'{}' is {} and {} at step {}",
+            self.signal, self.first, self.second, self.step
+        )
+    }
+}
+
+impl Witness {
+    /// Merges witnesses of the same model into a single witness. The merged witness lists the
+    /// properties of all witnesses and the frames contain the assignments of all witnesses at the
+    /// same step. All witnesses must have the same number of frames and agree on the bit widths of
+    /// their signals. Signals that are assigned different values at the same step are returned
+    /// as conflicts.
+    pub fn merge(witnesses: Vec<Witness>) -> Result<(Witness, Vec<MergeConflict>)> {
+        let frame_count = |witness: &Witness| -> usize {
+            witness.formats.iter().map(|fmt| fmt.frames.len()).sum()
+        };
+
+        let expected_frames = witnesses
+            .first()
+            .map(frame_count)
+            .ok_or_else(|| PeriscopeError::InvalidInput(String::from("No witnesses to merge.")))?;
+
+        let mut widths: HashMap<String, usize> = HashMap::new();
+        let mut values: HashMap<(String, u64), u64> = HashMap::new();
+        let mut conflicts = Vec::new();
+
+        for (idx, witness) in witnesses.iter().enumerate() {
+            let frames = frame_count(witness);
+
+            if frames != expected_frames {
+                return Err(PeriscopeError::ModelMismatch(format!(
+                    "Witness {} has {frames} frames, but the first witness has {expected_frames}.",
+                    idx + 1
+                )));
+            }
+
+            for (prefix, step, assignment) in witness.prefixed_assignments() {
+                let Some(symbol) = &assignment.symbol else {
+                    continue;
+                };
+
+                let name = format!("{prefix}{symbol}");
+                let bits = assignment.kind.bits();
+
+                match widths.entry(name.clone()) {
+                    Entry::Occupied(entry) if *entry.get() != bits => {
+                        return Err(PeriscopeError::ModelMismatch(format!(
+                            "Signal '{symbol}' has {} bits in one witness and {bits} bits in another.",
+                            entry.get()
+                        )))
+                    }
+                    Entry::Occupied(_) => {}
+                    Entry::Vacant(entry) => {
+                        entry.insert(bits);
+                    }
+                }
+
+                let signal = match assignment.kind {
+                    AssignmentKind::Array { index, .. } => format!("{name}[{index}]"),
+                    AssignmentKind::BitVec { .. } => name,
+                };
+
+                let value = assignment.get_value();
+
+                match values.entry((signal, step)) {
+                    Entry::Occupied(entry) if *entry.get() != value => {
+                        conflicts.push(MergeConflict {
+                            signal: entry.key().0.clone(),
+                            step,
+                            first: *entry.get(),
+                            second: value,
+                        })
+                    }
+                    Entry::Occupied(_) => {}
+                    Entry::Vacant(entry) => {
+                        entry.insert(value);
+                    }
+                }
+            }
+        }
+
+        // all witnesses are of the same model
+        let array_sorts = witnesses[0].array_sorts.clone();
+        let outputs = witnesses[0].outputs.clone();
+        // the merged witness satisfies the properties of all witnesses
+        let cone_of_influence = witnesses
+            .iter()
+            .flat_map(|witness| witness.cone_of_influence.iter().cloned())
+            .collect();
+
+        let mut witnesses = witnesses.into_iter();
+        let mut merged = witnesses
+            .next()
+            .map(Witness::into_single_block)
+            .unwrap_or_default();
+
+        for witness in witnesses.map(Witness::into_single_block) {
+            for prop in witness.header.props {
+                let listed = merged
+                    .header
+                    .props
+                    .iter()
+                    .any(|listed| listed.kind == prop.kind && listed.idx == prop.idx);
+
+                if !listed {
+                    merged.header.props.push(prop);
+                }
+            }
+
+            for (frame, other) in merged.frames.iter_mut().zip(witness.frames) {
+                match (&mut frame.state_part, other.state_part) {
+                    (Some(state_part), Some(other)) => {
+                        merge_models(&mut state_part.model, other.model)
+                    }
+                    (state_part @ None, other) => *state_part = other,
+                    (Some(_), None) => {}
+                }
+
+                merge_models(&mut frame.input_part.model, other.input_part.model);
+            }
+        }
+
+        let merged = Witness {
+            formats: vec![merged],
+            array_sorts,
+            outputs,
+            cone_of_influence,
+        };
+
+        Ok((merged, conflicts))
+    }
+
+    /// Joins all blocks of the witness into one block.
+    fn into_single_block(self) -> WitnessFormat {
+        let mut formats = self.formats.into_iter();
+        let mut single = formats.next().unwrap_or_default();
+
+        for format in formats {
+            single.header.props.extend(format.header.props);
+            single.frames.extend(format.frames);
+        }
+
+        single
+    }
+
+    /// All assignments of the witness together with their step, states prefixed with '#' and
+    /// inputs with '@'.
+    fn prefixed_assignments(
+        &self,
+    ) -> impl Iterator<Item = (&'static str, u64, &super::assignment::Assignment)> {
+        self.formats
+            .iter()
+            .flat_map(|fmt| &fmt.frames)
+            .flat_map(|frame| {
+                let states = frame.state_part.iter().flat_map(|part| {
+                    part.model
+                        .assignments
+                        .iter()
+                        .map(move |assignment| ("#", part.step, assignment))
+                });
+
+                let inputs = frame
+                    .input_part
+                    .model
+                    .assignments
+                    .iter()
+                    .map(move |assignment| ("@", frame.input_part.step, assignment));
+
+                states.chain(inputs)
+            })
+    }
+}
+
+/// Adds the assignments of `other` that are not yet part of `model`.
+fn merge_models(model: &mut Model, other: Model) {
+    for assignment in other.assignments {
+        if !model.assignments.contains(&assignment) {
+            model.assignments.push(assignment);
+        }
+    }
+}
@@ -1,80 +1,290 @@
 mod assignment;
 mod btor2;
+mod collapsed;
+mod diff;
+mod expect;
+mod explain;
+mod flow;
 mod helpers;
+mod input;
+mod markdown;
+mod merge;
+mod options;
+mod protobuf;
+mod regex;
+mod template;
 mod witness_format;
 
 use std::{
-    collections::{BTreeMap, HashMap},
-    fmt::Write,
-    io::Read,
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet},
+    fs::File,
+    io::{Read, Write},
+    path::Path,
     str::FromStr,
 };
 
+use crate::{
+    error::{self, ParseError, PeriscopeError},
+    output,
+};
+use serde::{Deserialize, Serialize};
+
 use nom::{branch, combinator, multi};
 
 use self::{
-    assignment::Assignment,
-    btor2::Property,
+    template::FlowLine,
     witness_format::{WitnessFormat, WitnessFrame},
 };
 
-pub use witness_format::{Prop, PropKind, PropVec};
+pub use assignment::{Assignment, AssignmentKind};
+pub use btor2::{
+    check_node_ids, check_property_kinds, count_declarations, get_array_sorts,
+    get_declared_signals, get_outputs, get_properties, ArraySort, Btor2Problem, DeclarationCounts,
+    Output, Property,
+};
+pub use diff::{SignalDiff, WitnessDiff};
+pub use explain::explain_line;
+pub use flow::{Flow, FlowType};
+pub use input::{accept_witness, open_maybe_compressed, split_model, split_witnesses};
+pub use merge::MergeConflict;
+pub use options::{
+    BitOrder, ByteOrder, FixedPoint, ListFormat, OutputFormat, ParseOptions, Radix, Rename,
+    ReportOptions,
+};
+pub use template::FlowTemplate;
+pub use witness_format::{Prop, PropKind, PropTags, PropVec};
 
 pub fn parse_btor_witness<I: Read>(
     mut input: I,
     btor2: Option<impl Read>,
-) -> anyhow::Result<Witness> {
+    options: &ParseOptions,
+) -> error::Result<WitnessOutcome> {
     let mut buf = String::new();
-    let _ = input.read_to_string(&mut buf);
+    input.read_to_string(&mut buf)?;
+
+    let mut outcome = WitnessOutcome::parse(&buf, options).map_err(PeriscopeError::ParseFailed)?;
+
+    if let (WitnessOutcome::Sat(witness), Some(mut btor2)) = (&mut outcome, btor2) {
+        let mut model = String::new();
+        btor2.read_to_string(&mut model)?;
+
+        witness.add_prop_names(btor2::get_property_names(model.as_bytes()));
+        witness.array_sorts = btor2::get_array_sorts(model.as_bytes());
+        witness.outputs = btor2::get_outputs(model.as_bytes());
+        witness.warn_unevidenced_props(&btor2::get_signal_nodes(model.as_bytes()));
+
+        let props: Vec<usize> = witness
+            .formats
+            .iter()
+            .flat_map(|fmt| &fmt.header.props)
+            .filter_map(|prop| prop.property.as_ref().map(|property| property.node))
+            .collect();
+        witness.cone_of_influence = btor2::cone_of_influence(model.as_bytes(), &props);
+    }
 
-    let mut witness = Witness::from_str(&buf)
-        .map_err(|err| anyhow::format_err!("Failed to parse witness. Cause: {err}"))?;
+    if let (WitnessOutcome::Sat(witness), Some(path)) = (&mut outcome, &options.names) {
+        let names = std::fs::read_to_string(path).map_err(PeriscopeError::file(path))?;
+        let names = btor2::parse_property_names(&names).map_err(|err| {
+            PeriscopeError::InvalidInput(format!("Invalid names file '{}': {err}", path.display()))
+        })?;
 
-    if let Some(btor2_prop_names) = btor2.map(|inner| btor2::get_property_names(inner)) {
-        witness.add_prop_names(btor2_prop_names);
+        witness.add_names(&names);
     }
 
-    Ok(witness)
+    Ok(outcome)
+}
+
+/// Parses the witness stored at `path`, which may be compressed (see [`open_maybe_compressed`]).
+/// If the path to the BTOR2 model is provided, the property names from the model are added to the
+/// witness.
+pub fn analyze_witness_file(
+    path: impl AsRef<Path>,
+    btor2: Option<impl AsRef<Path>>,
+    options: &ParseOptions,
+) -> error::Result<WitnessOutcome> {
+    let witness = open_maybe_compressed(path)?;
+    let btor2 = btor2.map(open_btor2).transpose()?;
+
+    parse_btor_witness(witness, btor2, options)
+}
+
+/// Parses the witness and returns the JSON report of its flows, as written by
+/// 'parse-witness --format json', or `null` if btormc reported 'unsat'. Only the witness text is
+/// used, so this works without a filesystem, e.g. when periscope is embedded in another tool.
+pub fn analyze_witness(input: &str) -> error::Result<String> {
+    let outcome = parse_btor_witness(input.as_bytes(), None::<&[u8]>, &ParseOptions::default())?;
+
+    let WitnessOutcome::Sat(witness) = outcome else {
+        return Ok(String::from("null"));
+    };
+
+    let options = ReportOptions {
+        format: OutputFormat::Json,
+        ..Default::default()
+    };
+
+    let mut report = Vec::new();
+    witness.analyze_and_report(&options, &mut report)?;
+
+    Ok(String::from_utf8_lossy(&report).trim_end().to_string())
+}
+
+/// Opens the BTOR2 model file at `path`.
+pub fn open_btor2(path: impl AsRef<Path>) -> error::Result<File> {
+    let path = path.as_ref();
+
+    File::open(path).map_err(PeriscopeError::file(path))
+}
+
+/// Reads the bad and justice properties declared in the BTOR2 model at `path`, by their index in
+/// the witness header.
+pub fn parse_btor2_properties(path: impl AsRef<Path>) -> error::Result<BTreeMap<u64, Property>> {
+    let model = open_btor2(path)?;
+
+    Ok(btor2::get_property_names(model).into_iter().collect())
 }
 
+/// Result of running `btormc` on a model.
 #[derive(Debug, Clone)]
+pub enum WitnessOutcome {
+    /// `btormc` found a counterexample, described by the witness.
+    Sat(Witness),
+
+    /// `btormc` reported `unsat`, i.e. no property could be satisfied.
+    Unsat,
+}
+
+impl WitnessOutcome {
+    /// The witness of the counterexample, or [`PeriscopeError::UnsatInput`] if there is none.
+    pub fn into_witness(self) -> error::Result<Witness> {
+        match self {
+            WitnessOutcome::Sat(witness) => Ok(witness),
+            WitnessOutcome::Unsat => Err(PeriscopeError::UnsatInput),
+        }
+    }
+}
+
+impl WitnessOutcome {
+    /// Parses the output of `btormc`, which is either `unsat` or a witness.
+    pub fn parse(input: &str, options: &ParseOptions) -> Result<Self, ParseError> {
+        if input.trim() == "unsat" {
+            return Ok(WitnessOutcome::Unsat);
+        }
+
+        Witness::parse(input, options).map(WitnessOutcome::Sat)
+    }
+}
+
+impl FromStr for WitnessOutcome {
+    type Err = ParseError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        Self::parse(input, &ParseOptions::default())
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Witness {
     pub formats: Vec<WitnessFormat>,
+
+    /// Declared sorts of array signals, known if the BTOR2 model is provided.
+    #[serde(skip)]
+    array_sorts: HashMap<String, ArraySort>,
+
+    /// Outputs declared in the BTOR2 model, known if the model is provided.
+    #[serde(skip)]
+    outputs: Vec<Output>,
+
+    /// Inputs and states that the satisfied properties depend on, known if the BTOR2 model is
+    /// provided.
+    #[serde(skip)]
+    cone_of_influence: HashSet<String>,
 }
 
 impl FromStr for Witness {
-    type Err = String;
+    type Err = ParseError;
 
     fn from_str(input: &str) -> Result<Self, Self::Err> {
+        Self::parse(input, &ParseOptions::default())
+    }
+}
+
+impl Witness {
+    /// Parses a witness consisting of one or more witness formats.
+    pub fn parse(input: &str, options: &ParseOptions) -> Result<Self, ParseError> {
         if input.is_empty() {
-            return Err(String::from("No satisfiable property found."));
+            return Err(ParseError::input("No input received."));
+        }
+
+        if input.trim().is_empty() {
+            return Err(ParseError::input("Empty witness."));
         }
 
         let comment_parser = combinator::map(multi::many1(helpers::comment), |_| vec![]);
-        let whole_parser = branch::alt((comment_parser, multi::many1(WitnessFormat::parse)));
+        let whole_parser = branch::alt((
+            comment_parser,
+            multi::many1(|input| WitnessFormat::parse(options, input)),
+        ));
 
-        let mut witness_parser =
-            combinator::map(whole_parser, |parsed| Witness { formats: parsed });
+        let mut witness_parser = combinator::map(whole_parser, |parsed| Witness {
+            formats: parsed,
+            array_sorts: HashMap::new(),
+            outputs: Vec::new(),
+            cone_of_influence: HashSet::new(),
+        });
 
         match witness_parser(input) {
-            Ok((rest, witness)) => {
+            Ok((rest, mut witness)) => {
                 if !rest.is_empty() {
-                    Err(format!("Could not parse full input. Remaining: {rest}"))
+                    let mut err = ParseError::at(input, rest, None);
+                    err.message = String::from("Could not parse full input");
+                    Err(err)
                 } else {
+                    witness.number_blocks();
                     Ok(witness)
                 }
             }
-            Err(err) => Err(err.to_string()),
+            Err(nom::Err::Error(err) | nom::Err::Failure(err)) => {
+                let mut error = ParseError::at(input, err.input, Some(err.code));
+
+                if let Some(tag) = options.prop_tags.unknown_tag(input, err.input) {
+                    error.message = format!(
+                        "Unknown property kind '{tag}' in the witness header (see '--prop-tags')"
+                    );
+                }
+
+                Err(error)
+            }
+            Err(nom::Err::Incomplete(_)) => Err(ParseError::input("Incomplete witness.")),
         }
     }
 }
 
-enum FlowType {
-    State,
-    Input,
+/// Size of a witness in terms of frames and assignments.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct WitnessCounts {
+    pub frames: usize,
+    pub input_assignments: usize,
+    pub state_assignments: usize,
+    /// Number of distinct named inputs and states.
+    pub signals: usize,
 }
 
 impl Witness {
+    /// Stores the position of its block in each property if the witness has more than one block,
+    /// so that properties with the same index in different blocks can be told apart.
+    fn number_blocks(&mut self) {
+        if self.formats.len() < 2 {
+            return;
+        }
+
+        for (block, format) in self.formats.iter_mut().enumerate() {
+            for prop in &mut format.header.props {
+                prop.block = Some(block);
+            }
+        }
+    }
+
     pub fn props_in_steps(&self) -> Vec<(PropVec, usize)> {
         let mut res = Vec::with_capacity(self.formats.len());
 
@@ -86,167 +296,1870 @@ impl Witness {
         res
     }
 
-    pub fn analyze_and_report(&self) {
-        for (props, steps) in self.props_in_steps() {
-            let props = props
-                .inner
-                .iter()
-                .map(|prop| {
-                    let mut prop_string = prop.to_string();
-
-                    if matches!(&prop.property, Some(property) if property.name.is_some()) {
-                        let property = prop.property.as_ref().unwrap();
-                        let _ = write!(
-                            &mut prop_string,
-                            " named '{}' with nid: {}",
-                            property.name.as_ref().unwrap(),
-                            property.node
-                        );
-                    }
+    /// Counts frames and assignments in the witness without analyzing the flows.
+    pub fn counts(&self) -> WitnessCounts {
+        let mut counts = WitnessCounts::default();
+        let mut inputs = HashSet::new();
+        let mut states = HashSet::new();
 
-                    prop_string
-                })
-                .collect::<Vec<_>>()
-                .join(", ");
+        for frame in self.formats.iter().flat_map(|fmt| &fmt.frames) {
+            counts.frames += 1;
+
+            let input_assignments = &frame.input_part.model.assignments;
+            counts.input_assignments += input_assignments.len();
+            inputs.extend(input_assignments.iter().filter_map(|a| a.symbol.as_ref()));
 
-            println!("Satisifed properties in {} steps:\n    {}\n", steps, props,);
+            if let Some(state_part) = &frame.state_part {
+                let state_assignments = &state_part.model.assignments;
+                counts.state_assignments += state_assignments.len();
+                states.extend(state_assignments.iter().filter_map(|a| a.symbol.as_ref()));
+            }
         }
 
-        self.analyze_input_flow();
-        self.analyze_state_flow();
+        counts.signals = inputs.len() + states.len();
+
+        counts
     }
 
-    fn collect_assignments<'a, I>(iter: I) -> (BTreeMap<String, Vec<(u64, Assignment)>>, u64)
-    where
-        I: Iterator<Item = (&'a WitnessFrame, &'a Assignment)>,
-    {
-        let mut inputs: BTreeMap<String, Vec<(u64, Assignment)>> = BTreeMap::new();
-        let mut max_step = 1;
+    /// Sum of the bit widths of all distinct named inputs and states, a measure of how much of
+    /// the state space the witness covers. For arrays, the width of the values is counted. A
+    /// signal whose width changes is counted with its largest width.
+    pub fn total_bits_touched(&self) -> usize {
+        let mut widths: HashMap<(FlowType, &str), usize> = HashMap::new();
 
-        for (idx, (frame, input)) in iter.enumerate() {
-            let step = frame.input_part.step;
+        for frame in self.formats.iter().flat_map(|fmt| &fmt.frames) {
+            let inputs = frame
+                .input_part
+                .model
+                .assignments
+                .iter()
+                .map(|a| (FlowType::Input, a));
+            let states = frame
+                .state_part
+                .iter()
+                .flat_map(|sp| &sp.model.assignments)
+                .map(|a| (FlowType::State, a));
 
-            if step > max_step {
-                max_step = step;
+            for (flow_type, assignment) in inputs.chain(states) {
+                if let Some(symbol) = &assignment.symbol {
+                    let width = widths.entry((flow_type, symbol)).or_default();
+                    *width = (*width).max(assignment.kind.bits());
+                }
             }
+        }
 
-            let idx_as_str = idx.to_string();
-            let name = input.symbol.clone().unwrap_or(idx_as_str);
+        widths.values().sum()
+    }
 
-            let entry = inputs.entry(name).or_default();
+    /// Names of the signals in the witness that are not part of `declared`, sorted by name.
+    pub fn undeclared_signals(&self, declared: &HashSet<String>) -> Vec<&str> {
+        let signals: BTreeSet<&str> = self
+            .formats
+            .iter()
+            .flat_map(|fmt| &fmt.frames)
+            .flat_map(|frame| {
+                let states = frame.state_part.iter().flat_map(|sp| &sp.model.assignments);
+                states.chain(&frame.input_part.model.assignments)
+            })
+            .filter_map(|assignment| assignment.symbol.as_deref())
+            .collect();
 
-            let value = input.get_value();
+        signals
+            .into_iter()
+            .filter(|signal| !declared.contains(*signal))
+            .collect()
+    }
 
-            match entry.last() {
-                Some((_, last_assignment)) => {
-                    if last_assignment.get_value() != value {
-                        entry.push((step, input.clone()));
-                    }
+    pub fn analyze_and_report(
+        &self,
+        options: &ReportOptions,
+        out: &mut dyn Write,
+    ) -> error::Result<()> {
+        // reject an invalid frame range before anything is printed
+        self.frame_range(options)?;
+
+        if options.count {
+            let counts = self.counts();
+
+            writeln!(out, "Frames: {}", counts.frames)?;
+            writeln!(out, "Input assignments: {}", counts.input_assignments)?;
+            writeln!(out, "State assignments: {}", counts.state_assignments)?;
+            writeln!(out, "Distinct signals: {}", counts.signals)?;
+            writeln!(out, "Bits touched: {}", self.total_bits_touched())?;
+
+            return Ok(());
+        }
+
+        if options.format == OutputFormat::Md {
+            for (mut props, steps) in self.props_in_steps() {
+                props.inner.retain(|prop| options.shows_kind(prop.kind));
+                markdown::write_props(out, &props, steps, options.show_source)?;
+            }
+
+            for flow in [
+                (!options.no_input).then(|| self.analyze_input_flow(options)),
+                (!options.no_state).then(|| self.analyze_state_flow(options)),
+            ]
+            .into_iter()
+            .flatten()
+            {
+                markdown::write_flow(out, &flow?, options.radix)?;
+            }
+
+            return Ok(());
+        }
+
+        if let Some(path) = &options.expect {
+            return self.check_expected(out, path, options);
+        }
+
+        if options.format == OutputFormat::Json {
+            let flows = self.analyze_flows(options)?;
+            serde_json::to_writer_pretty(&mut *out, &flows).map_err(std::io::Error::from)?;
+            writeln!(out)?;
+
+            return Ok(());
+        }
+
+        if options.format == OutputFormat::Collapsed {
+            for flow in self.analyze_flows(options)? {
+                collapsed::write_flow(out, &flow)?;
+            }
+
+            return Ok(());
+        }
+
+        if options.format == OutputFormat::Protobuf {
+            let mut props = self.props_in_steps();
+            for (props, _) in &mut props {
+                props.inner.retain(|prop| options.shows_kind(prop.kind));
+            }
+
+            let flows = self.analyze_flows(options)?;
+            protobuf::write_witness(out, &props, &flows)?;
+
+            return Ok(());
+        }
+
+        for (mut props, steps) in self.props_in_steps() {
+            props.inner.retain(|prop| options.shows_kind(prop.kind));
+
+            writeln!(
+                out,
+                "Satisifed properties in {} steps:\n    {}",
+                steps,
+                props.formatted_string(),
+            )?;
+
+            if options.show_source {
+                for property in props.inner.iter().filter_map(|prop| prop.property.as_ref()) {
+                    writeln!(out, "        {}", property.source)?;
                 }
-                None => entry.push((step, input.clone())),
             }
+
+            writeln!(out)?;
         }
 
-        (inputs, max_step)
-    }
+        if options.show_outputs {
+            self.print_outputs(out)?;
+        }
 
-    fn print_flow(
-        inputs: &BTreeMap<String, Vec<(u64, Assignment)>>,
-        max_step: u64,
-        flow_type: FlowType,
-    ) {
-        let indent = " ".repeat(4);
+        if options.show_empty {
+            self.print_empty_steps(out, options)?;
+        }
 
-        let prefix = match flow_type {
-            FlowType::State => "#",
-            FlowType::Input => "@",
-        };
+        if options.init_only {
+            return self.print_initial_state(out);
+        }
 
-        for (name, flow) in inputs.iter() {
-            println!("{indent}{}: ", name);
+        let inputs = (!options.no_input)
+            .then(|| self.analyze_input_flow(options))
+            .transpose()?;
+        let states = (!options.no_state)
+            .then(|| self.analyze_state_flow(options))
+            .transpose()?;
 
-            let largest_val = flow
-                .iter()
-                .map(|(_, assignment)| assignment.get_value())
-                .max()
-                .unwrap_or(1)
-                .max(1);
+        let mut flows: Vec<Flow> = [inputs, states].into_iter().flatten().collect();
 
-            let width = max_step.ilog10() as usize + 1;
-            let val_width = largest_val.ilog10() as usize + 1;
+        if let Some(top) = options.top {
+            keep_most_active(&mut flows, top);
+        }
 
-            for (idx, (step, assignment)) in flow.iter().enumerate() {
-                print!("{indent}{indent}");
+        let flows = flows.into_iter();
 
-                if idx > 0 {
-                    print!("-> ");
-                } else {
-                    print!("   ");
+        if options.signal_stats {
+            for flow in flows {
+                match flow.flow_type() {
+                    FlowType::Input => writeln!(out, "Inputs:")?,
+                    FlowType::State => writeln!(out, "States:")?,
                 }
 
-                println!(
-                    "{}{:>w$}: {:>v_w$} ({})",
-                    prefix,
-                    step,
-                    assignment.get_value(),
-                    assignment.kind.to_binary_string(),
-                    w = width,
-                    v_w = val_width,
-                );
+                print_signal_stats(out, &flow)?;
             }
 
-            println!(
-                "{indent}{indent}-> {}{:>w$}: end\n",
-                prefix,
-                max_step,
-                w = width
-            );
+            return Ok(());
+        }
+
+        if options.table {
+            let flows: Vec<_> = flows.collect();
+            return print_table(out, &flows, &options.signal, options.context);
+        }
+
+        if options.events {
+            let flows: Vec<_> = flows.collect();
+            return self.print_events(out, &flows, options);
+        }
+
+        if let Some(signal) = &options.histogram {
+            let flows: Vec<_> = flows.collect();
+            return print_histogram(out, &flows, signal);
+        }
+
+        if options.oscillating {
+            for flow in flows {
+                match flow.flow_type() {
+                    FlowType::Input => writeln!(out, "Inputs:")?,
+                    FlowType::State => writeln!(out, "States:")?,
+                }
+
+                print_oscillating(out, &flow)?;
+            }
+
+            return Ok(());
+        }
+
+        if options.first_change {
+            for flow in flows {
+                match flow.flow_type() {
+                    FlowType::Input => writeln!(out, "Inputs:")?,
+                    FlowType::State => writeln!(out, "States:")?,
+                }
+
+                print_first_changes(out, &flow)?;
+            }
+
+            return Ok(());
+        }
+
+        if options.longest_stable {
+            for flow in flows {
+                match flow.flow_type() {
+                    FlowType::Input => writeln!(out, "Inputs:")?,
+                    FlowType::State => writeln!(out, "States:")?,
+                }
+
+                print_longest_stable(out, &flow)?;
+            }
+
+            return Ok(());
+        }
+
+        if let Some(split_dir) = &options.split_dir {
+            std::fs::create_dir_all(split_dir).map_err(PeriscopeError::file(split_dir))?;
+
+            let mut signal_count = 0;
+
+            let printer = FlowPrinter::new(options);
+
+            for flow in flows {
+                signal_count += flow.len();
+
+                for (name, assignments) in flow.signals() {
+                    let path = split_dir.join(format!("{}.txt", sanitize_file_name(name)));
+                    let mut file = File::create(&path).map_err(PeriscopeError::file(&path))?;
+
+                    printer.print_signal(&mut file, &flow, name, assignments)?;
+                }
+            }
+
+            writeln!(
+                out,
+                "Wrote flows of {} signals to '{}'.",
+                signal_count,
+                split_dir.display()
+            )?;
+
+            return Ok(());
+        }
+
+        let printer = FlowPrinter::new(options);
+
+        if options.merge_flows {
+            let flows: Vec<_> = flows.collect();
+            printer.print_merged(out, &flows)?;
+            return Ok(());
+        }
+
+        for flow in flows {
+            match flow.flow_type() {
+                FlowType::Input => writeln!(out, "Inputs flow:")?,
+                FlowType::State => writeln!(out, "States flow:")?,
+            }
+
+            printer.print(out, &flow)?;
         }
+
+        Ok(())
     }
 
-    fn analyze_input_flow(&self) {
-        let frames_and_assignments =
-            self.formats
-                .iter()
-                .flat_map(|fmt| &fmt.frames)
-                .flat_map(|frame| {
-                    std::iter::repeat(frame).zip(frame.input_part.model.assignments.iter())
-                });
+    /// Flows of inputs and states, unless disabled in the options.
+    fn analyze_flows(&self, options: &ReportOptions) -> error::Result<Vec<Flow>> {
+        [
+            (!options.no_input).then(|| self.analyze_input_flow(options)),
+            (!options.no_state).then(|| self.analyze_state_flow(options)),
+        ]
+        .into_iter()
+        .flatten()
+        .collect()
+    }
 
-        let (inputs, max_step) = Self::collect_assignments(frames_and_assignments);
+    /// Compares the flows with the expected flows stored in `path` and prints the differences.
+    fn check_expected(
+        &self,
+        out: &mut dyn Write,
+        path: &Path,
+        options: &ReportOptions,
+    ) -> error::Result<()> {
+        let expected = std::fs::read_to_string(path).map_err(PeriscopeError::file(path))?;
+        let expected: Vec<Flow> = serde_json::from_str(&expected).map_err(|err| {
+            PeriscopeError::InvalidInput(format!(
+                "Invalid expected flows '{}': {err}",
+                path.display()
+            ))
+        })?;
 
-        println!("Inputs flow:");
+        let mismatches = expect::compare(&expected, &self.analyze_flows(options)?);
 
-        Self::print_flow(&inputs, max_step, FlowType::Input);
+        if mismatches.is_empty() {
+            writeln!(out, "Flows match the expected flows.")?;
+            return Ok(());
+        }
+
+        writeln!(out, "Flows differ from the expected flows:")?;
+        for mismatch in &mismatches {
+            writeln!(out, "    {mismatch}")?;
+        }
+
+        Err(PeriscopeError::ModelMismatch(format!(
+            "{} differences to the expected flows in '{}'.",
+            mismatches.len(),
+            path.display()
+        )))
     }
 
-    fn analyze_state_flow(&self) {
-        let frames_and_assignments =
-            self.formats
-                .iter()
-                .flat_map(|fmt| &fmt.frames)
-                .flat_map(|frame| {
-                    std::iter::repeat(frame).zip(
-                        frame
-                            .state_part
-                            .iter()
-                            .flat_map(|sp| sp.model.assignments.iter()),
-                    )
-                });
+    /// Prints the state part of the first frame, which holds the initial values of the states.
+    fn print_initial_state(&self, out: &mut dyn Write) -> error::Result<()> {
+        let state_part = self
+            .formats
+            .iter()
+            .flat_map(|fmt| &fmt.frames)
+            .next()
+            .and_then(|frame| frame.state_part.as_ref());
+
+        let Some(state_part) = state_part else {
+            writeln!(out, "Initial states:\n    (no assignments)\n")?;
+            return Ok(());
+        };
+
+        writeln!(out, "Initial states (#{}):", state_part.step)?;
 
-        let (inputs, max_step) = Self::collect_assignments(frames_and_assignments);
+        let mut rows = vec![["signal", "value", "binary"].map(String::from).to_vec()];
+        rows.extend(state_part.model.assignments.iter().map(|assignment| {
+            vec![
+                assignment.name(FlowType::State),
+                assignment.get_value().to_string(),
+                assignment.kind.to_binary_string(),
+            ]
+        }));
 
-        println!("States flow:");
-        Self::print_flow(&inputs, max_step, FlowType::State);
+        write_aligned_rows(out, &rows)?;
+        writeln!(out)?;
+
+        Ok(())
     }
 
-    fn add_prop_names(&mut self, mut btor2_prop_names: HashMap<u64, Property>) {
-        for format in &mut self.formats {
-            for prop in format.header.props.iter_mut() {
-                if let Some(property) = btor2_prop_names.remove(&prop.idx) {
-                    prop.property = Some(property);
+    /// Prints the outputs of the model. Outputs of an input or state are shown with the last value
+    /// of that signal in the witness.
+    fn print_outputs(&self, out: &mut dyn Write) -> error::Result<()> {
+        writeln!(out, "Declared outputs:")?;
+
+        if self.outputs.is_empty() {
+            writeln!(out, "    (none)")?;
+        }
+
+        let frames: Vec<WitnessFrame> = self
+            .formats
+            .iter()
+            .flat_map(|fmt| fmt.frames.iter().cloned())
+            .collect();
+
+        for output in &self.outputs {
+            write!(out, "    ")?;
+
+            match &output.name {
+                Some(name) => write!(out, "'{name}' with nid: {}", output.node)?,
+                None => write!(out, "nid: {}", output.node)?,
+            }
+
+            match &output.signal {
+                Some(signal) => match last_value(&frames, signal) {
+                    Some(value) => writeln!(out, ", signal '{signal}' ends at {value}")?,
+                    None => writeln!(out, ", signal '{signal}' is not in the witness")?,
+                },
+                None => writeln!(out)?,
+            }
+        }
+
+        writeln!(out)?;
+
+        Ok(())
+    }
+
+    /// Prints the steps of the selected frames whose input or state part has no assignments.
+    fn print_empty_steps(&self, out: &mut dyn Write, options: &ReportOptions) -> error::Result<()> {
+        writeln!(out, "Steps without assignments:")?;
+
+        let mut empty = 0;
+
+        for (_, frame) in self.selected_frames(options)? {
+            let parts = [
+                frame
+                    .state_part
+                    .as_ref()
+                    .filter(|_| !options.no_state)
+                    .map(|part| (FlowType::State, part)),
+                (!options.no_input).then_some((FlowType::Input, &frame.input_part)),
+            ];
+
+            for (flow_type, part) in parts.into_iter().flatten() {
+                if part.model.assignments.is_empty() {
+                    writeln!(
+                        out,
+                        "    {}{}: (no assignments)",
+                        flow_type.prefix(),
+                        part.step
+                    )?;
+                    empty += 1;
+                }
+            }
+        }
+
+        if empty == 0 {
+            writeln!(out, "    (none)")?;
+        }
+
+        writeln!(out)?;
+
+        Ok(())
+    }
+
+    /// Prints the changes of all signals grouped by step, followed by the properties satisfied at
+    /// that step. A witness satisfies its properties at its last frame.
+    fn print_events(
+        &self,
+        out: &mut dyn Write,
+        flows: &[Flow],
+        options: &ReportOptions,
+    ) -> error::Result<()> {
+        let mut changes = BTreeMap::<u64, Vec<(FlowType, &str, &Assignment)>>::new();
+
+        for flow in flows {
+            for (name, assignments) in flow.signals() {
+                for (step, assignment) in assignments {
+                    changes
+                        .entry(*step)
+                        .or_default()
+                        .push((flow.flow_type(), name, assignment));
                 }
             }
         }
+
+        let mut satisfied = BTreeMap::<u64, PropVec>::new();
+
+        for format in &self.formats {
+            let Some(last) = format.frames.last() else {
+                continue;
+            };
+
+            let props = satisfied.entry(last.input_part.step).or_default();
+            props.inner.extend(
+                format
+                    .header
+                    .props
+                    .iter()
+                    .filter(|prop| options.shows_kind(prop.kind))
+                    .cloned(),
+            );
+        }
+
+        let steps: BTreeSet<u64> = changes.keys().chain(satisfied.keys()).copied().collect();
+
+        for step in steps {
+            writeln!(out, "Step {step}:")?;
+
+            // inputs are listed before states, as they are collected in that order
+            for (flow_type, name, assignment) in changes.get(&step).into_iter().flatten() {
+                writeln!(
+                    out,
+                    "    {} {}: {} ({})",
+                    flow_type.name(),
+                    name,
+                    assignment.get_value(),
+                    assignment.kind.to_ordered_binary_string(options.bit_order)
+                )?;
+            }
+
+            if let Some(props) = satisfied.get(&step).filter(|props| !props.inner.is_empty()) {
+                writeln!(out, "    satisfied: {}", props.formatted_string())?;
+            }
+
+            writeln!(out)?;
+        }
+
+        Ok(())
+    }
+
+    fn collect_assignments<'a, I>(
+        &self,
+        iter: I,
+        flow_type: FlowType,
+        options: &ReportOptions,
+    ) -> error::Result<Flow>
+    where
+        I: Iterator<Item = ((usize, &'a WitnessFrame), &'a Assignment)>,
+    {
+        let mut inputs = BTreeMap::<String, Vec<(u64, Assignment)>>::new();
+        let mut widths: HashMap<String, usize> = HashMap::new();
+        let mut max_step = 0;
+        let mut unnamed = 0;
+        let mut out_of_range = HashSet::new();
+        let mut renamed: HashMap<String, String> = HashMap::new();
+        let mut merged_names = HashSet::new();
+        let mut block_ends: BTreeMap<usize, u64> = BTreeMap::new();
+        let mut block_starts: BTreeMap<String, Vec<(usize, usize)>> = BTreeMap::new();
+
+        for ((block, frame), input) in iter {
+            let step = frame.input_part.step;
+
+            if step > max_step {
+                max_step = step;
+            }
+
+            let block_end = block_ends.entry(block).or_insert(step);
+            *block_end = step.max(*block_end);
+
+            if input.symbol.is_none() {
+                unnamed += 1;
+            }
+
+            let name = input.name(flow_type);
+
+            if options.cone_of_influence && !self.cone_of_influence.contains(&name) {
+                continue;
+            }
+
+            let name = if options.rename.is_empty() {
+                name
+            } else {
+                let new_name = options
+                    .rename
+                    .iter()
+                    .fold(name.clone(), |name, rename| rename.apply(&name));
+
+                let first = renamed.entry(new_name.clone()).or_insert(name.clone());
+
+                if options.strict && *first != name && merged_names.insert(new_name.clone()) {
+                    output::warn(format!(
+                        "'{first}' and '{name}' are both renamed to '{new_name}', their flows are merged."
+                    ));
+                }
+
+                new_name
+            };
+
+            if options.strict {
+                let bits = input.kind.bits();
+                let first_bits = *widths.entry(name.clone()).or_insert(bits);
+
+                if first_bits != bits {
+                    return Err(PeriscopeError::ModelMismatch(format!(
+                        "Signal '{name}' changed its bit width from {first_bits} to {bits} at step {step}."
+                    )));
+                }
+            }
+
+            if let (AssignmentKind::Array { index, .. }, Some(sort)) =
+                (input.kind, self.array_sorts.get(&name))
+            {
+                let fits = sort.index_bits >= 64 || index >> sort.index_bits == 0;
+
+                if !fits && out_of_range.insert(name.clone()) {
+                    output::warn(format!(
+                        "index {index} of array '{name}' at step {step} exceeds its declared size {sort}."
+                    ));
+                }
+            }
+
+            let entry = inputs.entry(name.clone()).or_default();
+            let starts = block_starts.entry(name).or_default();
+
+            // steps restart in each block, so its first value is always kept
+            if starts
+                .last()
+                .is_none_or(|&(_, last_block)| last_block != block)
+            {
+                starts.push((entry.len(), block));
+                entry.push((step, input.clone()));
+                continue;
+            }
+
+            let value = input.get_value();
+
+            match entry.last() {
+                Some((_, last_assignment)) => {
+                    // writes of the same value to another index still change a sparse array
+                    let changed = if options.sparse_arrays() {
+                        last_assignment.kind != input.kind
+                    } else {
+                        last_assignment.get_value() != value
+                    };
+
+                    if changed {
+                        entry.push((step, input.clone()));
+                    }
+                }
+                None => entry.push((step, input.clone())),
+            }
+        }
+
+        if options.warn_unnamed && unnamed > 0 {
+            output::warn(format!(
+                "{unnamed} {} assignments have no symbol and were given positional names.",
+                flow_type.name()
+            ));
+        }
+
+        let array_sorts = self
+            .array_sorts
+            .iter()
+            .filter(|(name, _)| inputs.contains_key(*name))
+            .map(|(name, sort)| (name.clone(), *sort))
+            .collect();
+
+        let blocks = if block_ends.len() > 1 {
+            block_starts
+                .into_iter()
+                .map(|(name, starts)| {
+                    let starts = starts
+                        .into_iter()
+                        .map(|(pos, block)| (pos, block_ends[&block]))
+                        .collect();
+                    (name, starts)
+                })
+                .collect()
+        } else {
+            BTreeMap::new()
+        };
+
+        Ok(Flow::new(flow_type, inputs, max_step, array_sorts, blocks))
+    }
+
+    /// Positions of the first and last frame selected by `--from-frame` and `--to-frame`. Both
+    /// are clamped to the last frame of the witness, a range that ends before it starts is
+    /// rejected.
+    fn frame_range(&self, options: &ReportOptions) -> error::Result<(usize, usize)> {
+        if let (Some(from), Some(to)) = (options.from_frame, options.to_frame) {
+            if to < from {
+                return Err(PeriscopeError::InvalidInput(format!(
+                    "'--to-frame' {to} is before '--from-frame' {from}."
+                )));
+            }
+        }
+
+        let last = self
+            .formats
+            .iter()
+            .map(|fmt| fmt.frames.len())
+            .sum::<usize>()
+            .saturating_sub(1);
+
+        let from = options.from_frame.unwrap_or(0).min(last);
+        let to = options.to_frame.unwrap_or(last).min(last);
+
+        Ok((from, to))
+    }
+
+    /// Frames of the witness selected by `--from-frame` and `--to-frame`, each with the position
+    /// of its block. Both are positions in the list of all frames, independent of the step numbers
+    /// of the frames.
+    fn selected_frames<'a>(
+        &'a self,
+        options: &ReportOptions,
+    ) -> error::Result<impl Iterator<Item = (usize, &'a WitnessFrame)>> {
+        let (from, to) = self.frame_range(options)?;
+
+        Ok(self
+            .formats
+            .iter()
+            .enumerate()
+            .flat_map(|(block, fmt)| fmt.frames.iter().map(move |frame| (block, frame)))
+            .skip(from)
+            .take(to - from + 1))
+    }
+
+    /// Collects the assignments of inputs over the selected frames of the witness.
+    pub fn analyze_input_flow(&self, options: &ReportOptions) -> error::Result<Flow> {
+        if options.strict {
+            self.warn_duplicate_symbols(options, FlowType::Input)?;
+        }
+
+        let frames_and_assignments = self.selected_frames(options)?.flat_map(|(block, frame)| {
+            std::iter::repeat((block, frame)).zip(frame.input_part.model.assignments.iter())
+        });
+
+        self.collect_assignments(frames_and_assignments, FlowType::Input, options)
+    }
+
+    /// Collects the assignments of states over the selected frames of the witness.
+    pub fn analyze_state_flow(&self, options: &ReportOptions) -> error::Result<Flow> {
+        if options.strict {
+            self.warn_duplicate_symbols(options, FlowType::State)?;
+        }
+
+        let frames_and_assignments = self.selected_frames(options)?.flat_map(|(block, frame)| {
+            std::iter::repeat((block, frame)).zip(
+                frame
+                    .state_part
+                    .iter()
+                    .flat_map(|sp| sp.model.assignments.iter()),
+            )
+        });
+
+        self.collect_assignments(frames_and_assignments, FlowType::State, options)
+    }
+
+    /// Warns about symbols that are assigned more than once in the same frame. Only one of these
+    /// assignments would end up in the flow of the signal.
+    fn warn_duplicate_symbols(
+        &self,
+        options: &ReportOptions,
+        flow_type: FlowType,
+    ) -> error::Result<()> {
+        let (first_frame, _) = self.frame_range(options)?;
+
+        for (idx, (_, frame)) in self.selected_frames(options)?.enumerate() {
+            let assignments: Vec<&Assignment> = match flow_type {
+                FlowType::Input => frame.input_part.model.assignments.iter().collect(),
+                FlowType::State => frame
+                    .state_part
+                    .iter()
+                    .flat_map(|sp| &sp.model.assignments)
+                    .collect(),
+            };
+
+            let mut seen = HashSet::new();
+
+            for symbol in assignments.iter().filter_map(|a| a.symbol.as_deref()) {
+                if !seen.insert(symbol) {
+                    output::warn(format!(
+                        "{} '{symbol}' is assigned more than once in frame {}.",
+                        flow_type.name(),
+                        first_frame + idx
+                    ));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Warns about bad properties in the header that the frames give no evidence for. Only bad
+    /// properties on a single input or state of the model, `signals`, can be checked: the signal
+    /// must be non-zero at the last frame, or zero if the property negates it.
+    fn warn_unevidenced_props(&self, signals: &HashMap<usize, String>) {
+        for format in &self.formats {
+            for prop in &format.header.props {
+                let Some(property) = &prop.property else {
+                    continue;
+                };
+
+                let described = match &property.name {
+                    Some(name) => format!("{prop} named '{name}'"),
+                    None => prop.to_string(),
+                };
+
+                if format.frames.is_empty() {
+                    output::warn(format!(
+                        "{described} is listed in the header, but the witness has no frames."
+                    ));
+                    continue;
+                }
+
+                // bad properties are declared as '<nid> bad <node> [symbol]'
+                let mut tokens = property.source.split_whitespace().skip(1);
+                let (Some("bad"), Some(Ok(node))) =
+                    (tokens.next(), tokens.next().map(str::parse::<i64>))
+                else {
+                    continue;
+                };
+
+                let Some(signal) = signals.get(&(node.unsigned_abs() as usize)) else {
+                    continue;
+                };
+
+                let Some(value) = last_value(&format.frames, signal) else {
+                    continue;
+                };
+
+                if (value != 0) == (node < 0) {
+                    output::warn(format!(
+                        "{described} is listed in the header, but '{signal}' is {value} at the last frame."
+                    ));
+                }
+            }
+        }
+    }
+
+    /// Names the properties using a map from property index to name. The node ids of these
+    /// properties are unknown.
+    fn add_names(&mut self, names: &HashMap<u64, String>) {
+        for format in &mut self.formats {
+            for prop in format.header.props.iter_mut() {
+                if let Some(name) = names.get(&prop.idx) {
+                    prop.property = Some(Property {
+                        node: 0,
+                        _kind: prop.kind,
+                        name: Some(name.clone()),
+                        source: String::new(),
+                    });
+                }
+            }
+        }
+    }
+
+    fn add_prop_names(&mut self, btor2_prop_names: HashMap<u64, Property>) {
+        let mut witness_props: Vec<(PropKind, u64)> = Vec::new();
+        for prop in self.formats.iter().flat_map(|format| &format.header.props) {
+            if !witness_props.contains(&(prop.kind, prop.idx)) {
+                witness_props.push((prop.kind, prop.idx));
+            }
+        }
+
+        if witness_props.len() != btor2_prop_names.len() {
+            output::warn(format!(
+                "witness has {} properties, model declares {}. Is this the right model?",
+                witness_props.len(),
+                btor2_prop_names.len()
+            ));
+        }
+
+        for format in &mut self.formats {
+            for prop in format.header.props.iter_mut() {
+                // the same property can be satisfied in several blocks
+                if let Some(property) = btor2_prop_names.get(&prop.idx) {
+                    prop.property = Some(property.clone());
+                }
+            }
+        }
+    }
+}
+
+/// Latest value of the signal in the frames, `None` if it is never assigned.
+fn last_value(frames: &[WitnessFrame], signal: &str) -> Option<u64> {
+    frames.iter().rev().find_map(|frame| {
+        frame
+            .state_part
+            .iter()
+            .flat_map(|sp| &sp.model.assignments)
+            .chain(&frame.input_part.model.assignments)
+            .rfind(|assignment| assignment.symbol.as_deref() == Some(signal))
+            .map(Assignment::get_value)
+    })
+}
+
+/// Prints the flows of signals, one signal after another.
+pub(crate) struct FlowPrinter<'a> {
+    template: &'a FlowTemplate,
+    max_bits: Option<usize>,
+    bit_order: BitOrder,
+    byte_order: ByteOrder,
+    bit_diff: bool,
+    sparse_arrays: bool,
+    max_array_entries: Option<usize>,
+    reverse: bool,
+    end_marker: bool,
+    fixed_point: &'a [FixedPoint],
+}
+
+impl<'a> FlowPrinter<'a> {
+    pub(crate) fn new(options: &'a ReportOptions) -> Self {
+        Self {
+            template: &options.format_template,
+            max_bits: options.max_bits,
+            bit_order: options.bit_order,
+            byte_order: options.byte_order,
+            bit_diff: options.bit_diff,
+            sparse_arrays: options.sparse_arrays(),
+            max_array_entries: options.max_array_entries,
+            reverse: options.reverse,
+            end_marker: !options.no_end_marker,
+            fixed_point: &options.fixed_point,
+        }
+    }
+
+    fn print(&self, out: &mut dyn Write, flow: &Flow) -> std::io::Result<()> {
+        if flow.is_empty() {
+            writeln!(out, "    (no assignments)\n")?;
+        }
+
+        for (name, assignments) in flow.signals() {
+            self.print_signal(out, flow, name, assignments)?;
+        }
+
+        Ok(())
+    }
+
+    /// Prints the signals of all flows ordered by name, each tagged with the prefix of its flow.
+    /// Inputs come before states of the same name.
+    fn print_merged(&self, out: &mut dyn Write, flows: &[Flow]) -> std::io::Result<()> {
+        writeln!(out, "Merged flow:")?;
+
+        let mut signals: Vec<_> = flows
+            .iter()
+            .flat_map(|flow| flow.signals().map(move |signal| (flow, signal)))
+            .collect();
+
+        if signals.is_empty() {
+            writeln!(out, "    (no assignments)\n")?;
+        }
+
+        signals.sort_by_key(|(flow, (name, _))| (*name, flow.flow_type() != FlowType::Input));
+
+        for (flow, (name, assignments)) in signals {
+            let label = format!("{}{}", flow.flow_type().prefix(), name);
+            self.print_labeled(out, flow, &label, name, assignments)?;
+        }
+
+        Ok(())
+    }
+
+    pub(crate) fn print_signal(
+        &self,
+        out: &mut dyn Write,
+        flow: &Flow,
+        name: &str,
+        assignments: &[(u64, Assignment)],
+    ) -> std::io::Result<()> {
+        self.print_labeled(out, flow, name, name, assignments)
+    }
+
+    /// Prints the flow of the signal under the given label.
+    fn print_labeled(
+        &self,
+        out: &mut dyn Write,
+        flow: &Flow,
+        label: &str,
+        name: &str,
+        assignments: &[(u64, Assignment)],
+    ) -> std::io::Result<()> {
+        let indent = " ".repeat(4);
+        let widths = self.widths(flow, name, assignments);
+
+        match flow.array_sort(name) {
+            Some(sort) => writeln!(out, "{indent}{} {}: ", label, sort)?,
+            None => writeln!(out, "{indent}{}: ", label)?,
+        }
+
+        let end = format!(
+            "{}{:>w$}: end",
+            flow.flow_type().prefix(),
+            flow.max_step(),
+            w = widths.0
+        );
+
+        if self.reverse && self.end_marker {
+            writeln!(out, "{indent}{indent}   {end}")?;
+        }
+
+        let order: Box<dyn Iterator<Item = usize>> = if self.reverse {
+            Box::new((0..assignments.len()).rev())
+        } else {
+            Box::new(0..assignments.len())
+        };
+
+        // snapshots are built forwards, so a reversed flow collects them first
+        let mut snapshots: Box<dyn Iterator<Item = Option<String>>> =
+            match (self.sparse_arrays, self.reverse) {
+                (false, _) => Box::new(std::iter::empty()),
+                (true, false) => Box::new(sparse_snapshots(assignments, self.max_array_entries)),
+                (true, true) => Box::new(
+                    sparse_snapshots(assignments, self.max_array_entries)
+                        .collect::<Vec<_>>()
+                        .into_iter()
+                        .rev(),
+                ),
+            };
+
+        for (position, idx) in order.enumerate() {
+            write!(out, "{indent}{indent}")?;
+
+            match (self.reverse, position) {
+                (true, 0) if !self.end_marker => write!(out, "   ")?,
+                (true, _) => write!(out, "<- ")?,
+                (false, 0) => write!(out, "   ")?,
+                (false, _) => write!(out, "-> ")?,
+            }
+
+            writeln!(
+                out,
+                "{}",
+                self.render_line(flow, name, assignments, idx, widths)
+            )?;
+
+            if self.bit_diff && idx > 0 {
+                write_bit_diff(
+                    out,
+                    &assignments[idx - 1].1,
+                    &assignments[idx].1,
+                    self.bit_order,
+                )?;
+            }
+
+            if let Some(Some(snapshot)) = snapshots.next() {
+                writeln!(out, "{}{snapshot}", " ".repeat(15))?;
+            }
+        }
+
+        if self.reverse || !self.end_marker {
+            writeln!(out)
+        } else {
+            writeln!(out, "{indent}{indent}-> {end}\n")
+        }
+    }
+
+    /// Renders the assignment at `idx` of the signal's flow as a single line.
+    pub(crate) fn render_change(
+        &self,
+        flow: &Flow,
+        name: &str,
+        assignments: &[(u64, Assignment)],
+        idx: usize,
+    ) -> String {
+        let widths = self.widths(flow, name, assignments);
+        self.render_line(flow, name, assignments, idx, widths)
+    }
+
+    fn render_line(
+        &self,
+        flow: &Flow,
+        name: &str,
+        assignments: &[(u64, Assignment)],
+        idx: usize,
+        (step_width, value_width): (usize, usize),
+    ) -> String {
+        let (step, assignment) = &assignments[idx];
+
+        let line = FlowLine {
+            prefix: flow.flow_type().prefix(),
+            step: *step,
+            step_width,
+            name,
+            assignment,
+            value_width,
+            previous: idx.checked_sub(1).map(|prev| &assignments[prev].1),
+            max_bits: self.max_bits,
+            bit_order: self.bit_order,
+            byte_order: self.byte_order,
+            frac_bits: self.frac_bits(name),
+        };
+
+        self.template.render(&line)
+    }
+
+    fn frac_bits(&self, name: &str) -> Option<u32> {
+        self.fixed_point
+            .iter()
+            .find(|fixed| fixed.name == name)
+            .map(|fixed| fixed.frac_bits)
+    }
+
+    /// Widths of the widest step and widest value, so that the lines of a flow are aligned.
+    fn widths(&self, flow: &Flow, name: &str, assignments: &[(u64, Assignment)]) -> (usize, usize) {
+        let values = assignments
+            .iter()
+            .map(|(_, assignment)| assignment.get_value());
+
+        let value_width = match self.frac_bits(name) {
+            Some(frac_bits) => values
+                .map(|value| helpers::fixed_point(value, frac_bits).len())
+                .max(),
+            None => values.max().map(helpers::decimal_width),
+        };
+
+        (
+            helpers::decimal_width(flow.max_step()),
+            value_width.unwrap_or(1),
+        )
+    }
+}
+
+/// Writes the previous and new value of a bitvector in binary, with the flipped bits marked
+/// underneath. Nothing is written for arrays or if the bit widths differ.
+fn write_bit_diff(
+    out: &mut dyn Write,
+    previous: &Assignment,
+    current: &Assignment,
+    order: BitOrder,
+) -> std::io::Result<()> {
+    let (AssignmentKind::BitVec { bits, .. }, AssignmentKind::BitVec { bits: new_bits, .. }) =
+        (previous.kind, current.kind)
+    else {
+        return Ok(());
+    };
+
+    if bits != new_bits {
+        return Ok(());
+    }
+
+    let indent = " ".repeat(15);
+    let previous = previous.kind.to_ordered_binary_string(order);
+    let current = current.kind.to_ordered_binary_string(order);
+    let marks: String = previous
+        .chars()
+        .zip(current.chars())
+        .map(|(old, new)| if old == new { ' ' } else { '^' })
+        .collect();
+
+    writeln!(out, "{indent}was {previous}")?;
+    writeln!(out, "{indent}now {current}")?;
+    writeln!(out, "{indent}    {}", marks.trim_end())
+}
+
+/// Sparse map of an array after each of the assignments, with the values of all indices assigned
+/// up to and including it, ordered by index. The map is updated once per assignment, and only
+/// keeps the first `max_entries` distinct indices, further indices are only counted. The map is
+/// `None` as long as no index was assigned, e.g. for bitvectors.
+fn sparse_snapshots(
+    assignments: &[(u64, Assignment)],
+    max_entries: Option<usize>,
+) -> impl Iterator<Item = Option<String>> + '_ {
+    let mut values = BTreeMap::new();
+    let mut dropped = HashSet::new();
+
+    assignments.iter().map(move |(_, assignment)| {
+        if let AssignmentKind::Array { index, value, .. } = assignment.kind {
+            let full = max_entries.is_some_and(|max| values.len() >= max);
+
+            if full && !values.contains_key(&index) {
+                dropped.insert(index);
+            } else {
+                values.insert(index, value);
+            }
+        }
+
+        if values.is_empty() && dropped.is_empty() {
+            return None;
+        }
+
+        let mut entries: Vec<_> = values
+            .iter()
+            .map(|(index, value)| format!("{index}: {value}"))
+            .collect();
+
+        match dropped.len() {
+            0 => {}
+            1 => entries.push(String::from("... and 1 more entry")),
+            more => entries.push(format!("... and {more} more entries")),
+        }
+
+        Some(format!("{{{}}}", entries.join(", ")))
+    })
+}
+
+/// Replaces characters that are not safe in file names, such as path separators.
+fn sanitize_file_name(name: &str) -> String {
+    let sanitized: String = name
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.' | '[' | ']') {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+
+    if sanitized.chars().all(|c| c == '.') {
+        format!("_{sanitized}")
+    } else {
+        sanitized
+    }
+}
+
+/// Prints the first, last, smallest and largest value of each signal as an aligned table.
+fn print_signal_stats(out: &mut dyn Write, flow: &Flow) -> std::io::Result<()> {
+    let header = ["signal", "first", "last", "min", "max"]
+        .map(String::from)
+        .to_vec();
+    let mut rows = vec![header];
+
+    rows.extend(flow.signals().filter_map(|(name, assignments)| {
+        let values = assignments
+            .iter()
+            .map(|(_, assignment)| assignment.get_value());
+
+        Some(vec![
+            name.to_string(),
+            values.clone().next()?.to_string(),
+            values.clone().next_back()?.to_string(),
+            values.clone().min()?.to_string(),
+            values.max()?.to_string(),
+        ])
+    }));
+
+    write_aligned_rows(out, &rows)?;
+    writeln!(out)
+}
+
+/// Keeps the `top` signals of all flows that changed most often, ties are broken by name.
+fn keep_most_active(flows: &mut [Flow], top: usize) {
+    let mut signals: Vec<(usize, FlowType, String)> = flows
+        .iter()
+        .flat_map(|flow| {
+            flow.signals().map(|(name, assignments)| {
+                // the first assignment is the initial value, not a change
+                (assignments.len() - 1, flow.flow_type(), name.to_string())
+            })
+        })
+        .collect();
+
+    signals.sort_by(|(a_changes, _, a_name), (b_changes, _, b_name)| {
+        b_changes.cmp(a_changes).then(a_name.cmp(b_name))
+    });
+    signals.truncate(top);
+
+    for flow in flows {
+        let flow_type = flow.flow_type();
+        flow.retain(|name, _| {
+            signals
+                .iter()
+                .any(|(_, signal_type, signal)| *signal_type == flow_type && signal == name)
+        });
+    }
+}
+
+/// Prints the initial value of each signal and the first step at which it changed. The flows only
+/// contain changes, so these are the first two assignments of each signal.
+fn print_first_changes(out: &mut dyn Write, flow: &Flow) -> std::io::Result<()> {
+    for (name, assignments) in flow.signals() {
+        let Some((_, initial)) = assignments.first() else {
+            continue;
+        };
+
+        write!(out, "    {name}: initial {}", initial.get_value())?;
+
+        match assignments.get(1) {
+            Some((step, changed)) => writeln!(
+                out,
+                ", first changed at {}{step} to {}",
+                flow.flow_type().prefix(),
+                changed.get_value()
+            )?,
+            None => writeln!(out, ", never changed")?,
+        }
+    }
+
+    writeln!(out)
+}
+
+/// Prints how many steps the signal held each of its values as a bar chart, the most frequent
+/// values first.
+fn print_histogram(out: &mut dyn Write, flows: &[Flow], signal: &str) -> error::Result<()> {
+    const BAR_WIDTH: u64 = 40;
+
+    let Some(flow) = flows.iter().find(|flow| flow.get(signal).is_some()) else {
+        return Err(PeriscopeError::InvalidInput(format!(
+            "Signal '{signal}' is not assigned in the witness."
+        )));
+    };
+
+    let mut steps_by_value: BTreeMap<u64, u64> = BTreeMap::new();
+    for (_, assignment, steps) in flow.held_steps(signal) {
+        *steps_by_value.entry(assignment.get_value()).or_default() += steps;
+    }
+
+    let total: u64 = steps_by_value.values().sum();
+    let most = steps_by_value.values().max().copied().unwrap_or_default();
+
+    let mut values: Vec<_> = steps_by_value.into_iter().collect();
+    values.sort_by(|(a_value, a), (b_value, b)| b.cmp(a).then(a_value.cmp(b_value)));
+
+    writeln!(out, "Histogram of '{signal}' over {total} steps:")?;
+
+    let value_width = values
+        .iter()
+        .map(|(value, _)| helpers::decimal_width(*value))
+        .max()
+        .unwrap_or(1);
+
+    for (value, steps) in values {
+        let bar = "#".repeat((steps * BAR_WIDTH).div_ceil(most.max(1)) as usize);
+        let share = steps as f64 * 100.0 / total.max(1) as f64;
+
+        writeln!(
+            out,
+            "    {value:>value_width$}  {bar:<w$}  {steps} ({share:.1}%)",
+            w = BAR_WIDTH as usize
+        )?;
+    }
+
+    writeln!(out)?;
+
+    Ok(())
+}
+
+/// Prints the longest interval in which each signal kept its value, longest intervals first. An
+/// interval lasts from the step of a change until the next change, or until the end of its
+/// witness block for the last change in the block.
+fn print_longest_stable(out: &mut dyn Write, flow: &Flow) -> std::io::Result<()> {
+    let mut intervals: Vec<_> = flow
+        .signals()
+        .filter_map(|(name, _)| {
+            flow.intervals(name)
+                .map(|(from, assignment, next, block_end)| {
+                    let to = next.unwrap_or(block_end);
+                    (to.saturating_sub(from), from, to, assignment)
+                })
+                .enumerate()
+                // first of the longest intervals, steps restart in each block
+                .max_by(|(a_pos, a), (b_pos, b)| a.0.cmp(&b.0).then(b_pos.cmp(a_pos)))
+                .map(|(_, interval)| (name, interval))
+        })
+        .collect();
+
+    intervals.sort_by(|(a_name, a), (b_name, b)| b.0.cmp(&a.0).then(a_name.cmp(b_name)));
+
+    let header = ["signal", "steps", "from", "to", "value"]
+        .map(String::from)
+        .to_vec();
+    let mut rows = vec![header];
+
+    rows.extend(
+        intervals
+            .into_iter()
+            .map(|(name, (steps, from, to, assignment))| {
+                vec![
+                    name.to_string(),
+                    steps.to_string(),
+                    from.to_string(),
+                    to.to_string(),
+                    assignment.get_value().to_string(),
+                ]
+            }),
+    );
+
+    write_aligned_rows(out, &rows)?;
+    writeln!(out)
+}
+
+/// Run of a signal's flow that alternates between two values at a fixed period.
+struct Oscillation {
+    values: (u64, u64),
+    /// Number of steps after which the values repeat.
+    period: u64,
+    /// Number of full periods in the run.
+    repeats: usize,
+    from: u64,
+    to: u64,
+}
+
+/// Longest oscillation of the flow of a signal, if it repeats at least twice. The flow only
+/// contains changes, so it oscillates while every value equals the value two changes earlier.
+/// Steps restart in each block of a witness, so a run ends where the steps stop increasing.
+fn longest_oscillation(assignments: &[(u64, Assignment)]) -> Option<Oscillation> {
+    let mut longest: Option<(usize, usize)> = None;
+    let mut start = 0;
+
+    for end in 2..=assignments.len() {
+        let continues = assignments.get(end).is_some_and(|(step, assignment)| {
+            let (two_before, earlier) = &assignments[end - 2];
+            let before = assignments[end - 1].0;
+
+            // steps restart in each block of a multi-block witness, which ends the run
+            if *two_before >= before || before >= *step {
+                return false;
+            }
+
+            assignment.kind == earlier.kind
+                && (end < start + 3 || step - two_before == before - assignments[end - 3].0)
+        });
+
+        if continues {
+            continue;
+        }
+
+        // a run from `start` to `end - 1` ended, four changes make two full periods
+        if end - start >= 4 && longest.is_none_or(|(from, to)| end - start > to - from) {
+            longest = Some((start, end));
+        }
+
+        start = end - 1;
+    }
+
+    let (from, to) = longest?;
+    let run = &assignments[from..to];
+
+    Some(Oscillation {
+        values: (run[0].1.get_value(), run[1].1.get_value()),
+        period: run[2].0 - run[0].0,
+        repeats: run.len() / 2,
+        from: run[0].0,
+        to: run[run.len() - 1].0,
+    })
+}
+
+/// Prints the signals whose flow oscillates between two values, see [`longest_oscillation`].
+fn print_oscillating(out: &mut dyn Write, flow: &Flow) -> std::io::Result<()> {
+    let header = ["signal", "values", "period", "repeats", "from", "to"]
+        .map(String::from)
+        .to_vec();
+    let mut rows = vec![header];
+
+    rows.extend(flow.signals().filter_map(|(name, assignments)| {
+        let oscillation = longest_oscillation(assignments)?;
+
+        Some(vec![
+            name.to_string(),
+            format!("{}/{}", oscillation.values.0, oscillation.values.1),
+            oscillation.period.to_string(),
+            oscillation.repeats.to_string(),
+            oscillation.from.to_string(),
+            oscillation.to.to_string(),
+        ])
+    }));
+
+    if rows.len() == 1 {
+        return writeln!(out, "    (no oscillating signals)\n");
+    }
+
+    write_aligned_rows(out, &rows)?;
+    writeln!(out)
+}
+
+/// Number of rows after which the header of the table is repeated.
+const TABLE_HEADER_EVERY: usize = 20;
+
+/// Prints the values of signals at each step as a table, one row per step. All signals are shown
+/// if no signals are given.
+fn print_table(
+    out: &mut dyn Write,
+    flows: &[Flow],
+    signals: &[String],
+    context: Option<u64>,
+) -> error::Result<()> {
+    let columns: Vec<(&Flow, &str)> = if signals.is_empty() {
+        flows
+            .iter()
+            .flat_map(|flow| flow.signals().map(move |(name, _)| (flow, name)))
+            .collect()
+    } else {
+        signals
+            .iter()
+            .map(|name| {
+                flows
+                    .iter()
+                    .find(|flow| flow.get(name).is_some())
+                    .map(|flow| (flow, name.as_str()))
+                    .ok_or_else(|| {
+                        PeriscopeError::InvalidInput(format!(
+                            "Signal '{name}' is not in the witness."
+                        ))
+                    })
+            })
+            .collect::<error::Result<_>>()?
+    };
+
+    let max_step = flows.iter().map(Flow::max_step).max().unwrap_or_default();
+
+    // with context, only steps close to a change of a shown signal are printed
+    let shown: Box<dyn Fn(u64) -> bool> = match context {
+        Some(context) => {
+            let changes: BTreeSet<u64> = columns
+                .iter()
+                .flat_map(|(flow, name)| flow.get(name).unwrap_or_default())
+                .map(|(step, _)| *step)
+                .collect();
+
+            Box::new(move |step| {
+                changes
+                    .range(step.saturating_sub(context)..=step.saturating_add(context))
+                    .next()
+                    .is_some()
+            })
+        }
+        None => Box::new(|_| true),
+    };
+
+    let mut header = vec![String::from("step")];
+    header.extend(columns.iter().map(|(_, name)| name.to_string()));
+
+    enum Row {
+        Cells(Vec<String>),
+        Skipped(u64),
+    }
+
+    let mut rows = Vec::new();
+    let mut printed = 0;
+
+    for step in 0..=max_step {
+        if !shown(step) {
+            match rows.last_mut() {
+                Some(Row::Skipped(skipped)) => *skipped += 1,
+                _ => rows.push(Row::Skipped(1)),
+            }
+            continue;
+        }
+
+        if printed % TABLE_HEADER_EVERY == 0 {
+            rows.push(Row::Cells(header.clone()));
+        }
+        printed += 1;
+
+        let mut row = vec![step.to_string()];
+        row.extend(columns.iter().map(|(flow, name)| {
+            flow.value_at(name, step)
+                .map(|assignment| assignment.get_value().to_string())
+                .unwrap_or_else(|| String::from("-"))
+        }));
+
+        rows.push(Row::Cells(row));
+    }
+
+    let widths = column_widths(rows.iter().filter_map(|row| match row {
+        Row::Cells(cells) => Some(cells),
+        Row::Skipped(_) => None,
+    }));
+
+    for row in &rows {
+        match row {
+            Row::Cells(cells) => write_aligned_row(out, cells, &widths)?,
+            Row::Skipped(1) => writeln!(out, "    ... (skipped 1 step) ...")?,
+            Row::Skipped(skipped) => writeln!(out, "    ... (skipped {skipped} steps) ...")?,
+        }
+    }
+
+    writeln!(out)?;
+
+    Ok(())
+}
+
+/// Writes rows as a table with aligned columns. The first column is left-aligned, all other
+/// columns are right-aligned.
+fn write_aligned_rows(out: &mut dyn Write, rows: &[Vec<String>]) -> std::io::Result<()> {
+    let widths = column_widths(rows);
+
+    for row in rows {
+        write_aligned_row(out, row, &widths)?;
+    }
+
+    Ok(())
+}
+
+/// Width of the widest cell of each column.
+fn column_widths<'a>(rows: impl IntoIterator<Item = &'a Vec<String>>) -> Vec<usize> {
+    let mut widths: Vec<usize> = Vec::new();
+
+    for row in rows {
+        for (idx, cell) in row.iter().enumerate() {
+            match widths.get_mut(idx) {
+                Some(width) => *width = (*width).max(cell.len()),
+                None => widths.push(cell.len()),
+            }
+        }
+    }
+
+    widths
+}
+
+/// Writes a row of a table with the given column widths, see [`write_aligned_rows`].
+fn write_aligned_row(out: &mut dyn Write, row: &[String], widths: &[usize]) -> std::io::Result<()> {
+    write!(out, "    ")?;
+
+    for (idx, (cell, width)) in row.iter().zip(widths).enumerate() {
+        if idx == 0 {
+            write!(out, "{:<width$}", cell)?;
+        } else {
+            write!(out, "  {:>width$}", cell)?;
+        }
+    }
+
+    writeln!(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WITNESS: &str = "sat
+b0
+#0
+0 00000000 x#0
+@0
+0 00000001 in#0
+@1
+0 00000110 in#1
+#2
+0 00000011 x#2
+@2
+0 00000111 in#2
+.
+";
+
+    /// Two blocks, whose steps both start at 0. `in` toggles in every step.
+    const MULTI_BLOCK_WITNESS: &str = "sat
+b0
+#0
+@0
+0 0 in@0
+@1
+0 1 in@1
+@2
+0 0 in@2
+@3
+0 1 in@3
+@4
+0 0 in@4
+.
+sat
+b0
+#0
+@0
+0 1 in@0
+@1
+0 0 in@1
+.
+";
+
+    fn witness() -> Witness {
+        WITNESS.parse().expect("witness is valid")
+    }
+
+    fn frame_steps(witness: &Witness, options: &ReportOptions) -> Vec<u64> {
+        witness
+            .selected_frames(options)
+            .expect("frame range is valid")
+            .map(|(_, frame)| frame.input_part.step)
+            .collect()
+    }
+
+    #[test]
+    fn witness_without_frames_reports_empty_flows() {
+        let out = report("sat\nb0\n.\n", &ReportOptions::default());
+
+        assert!(out.contains("Inputs flow:\n    (no assignments)"));
+        assert!(out.contains("States flow:\n    (no assignments)"));
+    }
+
+    fn report(input: &str, options: &ReportOptions) -> String {
+        let witness: Witness = input.parse().expect("witness is valid");
+
+        let mut out = Vec::new();
+        witness
+            .analyze_and_report(options, &mut out)
+            .expect("report is written");
+
+        String::from_utf8(out).expect("report is UTF-8")
+    }
+
+    #[test]
+    fn single_step_zero_frame_is_reported() {
+        let out = report(
+            "sat\nb0\n#0\n0 00000000 x#0\n@0\n0 00000000 in@0\n.\n",
+            &ReportOptions::default(),
+        );
+
+        assert!(out.contains("    in: \n           @0: 0 (00000000)\n        -> @0: end\n"));
+        assert!(out.contains("    x: \n           #0: 0 (00000000)\n        -> #0: end\n"));
+    }
+
+    #[test]
+    fn all_zero_signal_is_reported() {
+        let out = report(
+            "sat\nb0\n@0\n0 0000 in@0\n@1\n0 0000 in@1\n@2\n0 0000 in@2\n.\n",
+            &ReportOptions::default(),
+        );
+
+        assert!(out.contains("    in: \n           @0: 0 (0000)\n        -> @2: end\n"));
+    }
+
+    #[test]
+    fn analyze_witness_returns_json_flows() {
+        let flows: serde_json::Value =
+            serde_json::from_str(&analyze_witness(WITNESS).expect("witness is valid"))
+                .expect("report is JSON");
+
+        assert_eq!(flows[0]["flow_type"], "input");
+        assert_eq!(flows[1]["flow_type"], "state");
+        assert_eq!(analyze_witness("unsat\n").expect("unsat is valid"), "null");
+    }
+
+    #[test]
+    fn unnamed_assignments_are_named_by_witness_position() {
+        let witness = "sat\nb0\n#0\n1 0001\n@0\n2 0000\n@1\n2 0011\n.\n";
+
+        let out = report(witness, &ReportOptions::default());
+        assert!(
+            out.contains("    #unnamed-@2: \n           @0: 0 (0000)\n        -> @1: 3 (0011)\n")
+        );
+        assert!(out.contains("    #unnamed-#1: \n"));
+
+        let options = ReportOptions {
+            init_only: true,
+            ..Default::default()
+        };
+        assert!(report(witness, &options).contains("#unnamed-#1"));
+    }
+
+    #[test]
+    fn arrays_are_sparse_by_default() {
+        let witness = "sat\nb0\n#0\n0 [00] 0001 mem#0\n@0\n#1\n0 [11] 0001 mem#1\n@1\n.\n";
+
+        let out = report(witness, &ReportOptions::default());
+        assert!(out.contains("{0: 1, 3: 1}"));
+
+        let options = ReportOptions {
+            no_sparse_arrays: true,
+            ..Default::default()
+        };
+        assert!(!report(witness, &options).contains('{'));
+    }
+
+    #[test]
+    fn frame_range_ending_before_start_is_rejected() {
+        let options = ReportOptions {
+            from_frame: Some(2),
+            to_frame: Some(1),
+            ..Default::default()
+        };
+
+        let err = witness().selected_frames(&options).err();
+        assert!(matches!(err, Some(PeriscopeError::InvalidInput(_))));
+    }
+
+    #[test]
+    fn from_frame_past_last_frame_is_clamped() {
+        let options = ReportOptions {
+            from_frame: Some(10),
+            ..Default::default()
+        };
+
+        assert_eq!(frame_steps(&witness(), &options), [2]);
+    }
+
+    #[test]
+    fn to_frame_past_last_frame_selects_up_to_the_end() {
+        let options = ReportOptions {
+            from_frame: Some(1),
+            to_frame: Some(10),
+            ..Default::default()
+        };
+
+        assert_eq!(frame_steps(&witness(), &options), [1, 2]);
+    }
+
+    #[test]
+    fn oscillation_ends_at_the_start_of_the_next_block() {
+        let options = ReportOptions {
+            oscillating: true,
+            ..Default::default()
+        };
+
+        let out = report(MULTI_BLOCK_WITNESS, &options);
+        assert!(out.contains("    in         0/1       2        2     0   4\n"));
+    }
+
+    #[test]
+    fn values_are_held_until_the_end_of_their_block() {
+        let options = ReportOptions {
+            format: OutputFormat::Collapsed,
+            ..Default::default()
+        };
+        let out = report(MULTI_BLOCK_WITNESS, &options);
+        let lines: Vec<_> = out
+            .lines()
+            .filter(|line| line.starts_with("input;"))
+            .collect();
+
+        assert_eq!(
+            lines,
+            [
+                "input;in;@0=0 1",
+                "input;in;@1=1 1",
+                "input;in;@2=0 1",
+                "input;in;@3=1 1",
+                "input;in;@4=0 1",
+                "input;in;@0=1 1",
+                "input;in;@1=0 1",
+            ]
+        );
+
+        let options = ReportOptions {
+            histogram: Some(String::from("in")),
+            ..Default::default()
+        };
+        assert!(report(MULTI_BLOCK_WITNESS, &options).contains("Histogram of 'in' over 7 steps:"));
+
+        let options = ReportOptions {
+            longest_stable: true,
+            ..Default::default()
+        };
+        assert!(
+            report(MULTI_BLOCK_WITNESS, &options).contains("    in          1     0   1      0\n")
+        );
+    }
+
+    #[test]
+    fn reversed_flow_shows_the_sparse_map_of_each_step() {
+        let witness = "sat\nb0\n#0\n0 [00] 0001 mem#0\n@0\n#1\n0 [11] 0010 mem#1\n@1\n.\n";
+        let options = ReportOptions {
+            reverse: true,
+            ..Default::default()
+        };
+
+        let out = report(witness, &options);
+        let first = out
+            .find("{0: 1, 3: 2}")
+            .expect("map of the last step is shown");
+        let last = out
+            .find("{0: 1}\n")
+            .expect("map of the first step is shown");
+        assert!(first < last);
+    }
+
+    #[test]
+    fn indices_past_max_array_entries_are_counted() {
+        let witness = "sat\nb0\n#0\n0 [00] 0001 mem#0\n@0\n#1\n0 [01] 0010 mem#1\n@1\n#2\n\
+            0 [10] 0011 mem#2\n@2\n#3\n0 [00] 0100 mem#3\n@3\n.\n";
+        let options = ReportOptions {
+            max_array_entries: Some(1),
+            ..Default::default()
+        };
+
+        let out = report(witness, &options);
+        let maps: Vec<_> = out
+            .lines()
+            .map(str::trim)
+            .filter(|line| line.starts_with('{'))
+            .collect();
+
+        assert_eq!(
+            maps,
+            [
+                "{0: 1}",
+                "{0: 1, ... and 1 more entry}",
+                "{0: 1, ... and 2 more entries}",
+                "{0: 4, ... and 2 more entries}",
+            ]
+        );
     }
 }
@@ -0,0 +1,461 @@
+use std::{path::PathBuf, str::FromStr};
+
+use clap::{ArgGroup, Args};
+
+use super::{regex::Regex, template::FlowTemplate, PropKind, PropTags};
+
+/// Options that control how a witness is parsed.
+#[derive(Debug, Default, Clone, Args)]
+pub struct ParseOptions {
+    /// Letters that mark properties in the witness header and the kinds they stand for, e.g.
+    /// 'b=bad,j=justice,c=constraint'. Useful for model checkers other than btormc. Besides the
+    /// kinds, 'assert' is accepted for safety properties and stands for 'bad', and 'fair' or
+    /// 'liveness' for 'justice'.
+    #[arg(long, value_name = "TAGS", default_value = PropTags::DEFAULT)]
+    pub prop_tags: PropTags,
+
+    /// File with property names, as an alternative to the BTOR2 model. Each line contains the
+    /// index of a property and its name, e.g. '0 overflow'. A JSON object mapping indices to
+    /// names, e.g. '{"0": "overflow"}', is accepted as well.
+    #[arg(long, value_name = "FILE", conflicts_with = "btor2")]
+    pub names: Option<PathBuf>,
+
+    /// Accept frames without step numbers, e.g. '@' instead of '@0', as written by some other
+    /// model checkers. The step of such a frame is its position in the witness.
+    #[arg(long)]
+    pub lenient: bool,
+}
+
+/// Format of the witness report.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum OutputFormat {
+    /// Plain text as shown in the terminal.
+    #[default]
+    Text,
+
+    /// Markdown tables, e.g. for GitHub issues or documentation.
+    Md,
+
+    /// JSON array with the flows of inputs and states. The output can be used as expected flows
+    /// for '--expect'.
+    Json,
+
+    /// Collapsed stacks '<flow type>;<signal>;<step>=<value> <steps>' for flamegraph tools, with
+    /// each value weighted by the number of steps the signal kept it.
+    Collapsed,
+
+    /// Binary protobuf `Witness` message as defined in 'proto/witness.proto', for ingestion by
+    /// other tools.
+    Protobuf,
+}
+
+impl OutputFormat {
+    /// Extension of files that contain a report in this format.
+    pub fn extension(self) -> &'static str {
+        match self {
+            OutputFormat::Text | OutputFormat::Collapsed => "txt",
+            OutputFormat::Md => "md",
+            OutputFormat::Json => "json",
+            OutputFormat::Protobuf => "pb",
+        }
+    }
+}
+
+/// Format of listings such as the properties of a model.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum ListFormat {
+    /// One entry per line.
+    #[default]
+    Text,
+
+    /// JSON array with one object per entry.
+    Json,
+}
+
+/// Order in which the bits of binary values are written.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum BitOrder {
+    /// Most significant bit first.
+    #[default]
+    Msb,
+
+    /// Least significant bit first.
+    Lsb,
+}
+
+/// Order in which the bytes of hexadecimal values are written.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum ByteOrder {
+    /// Most significant byte first.
+    #[default]
+    Be,
+
+    /// Least significant byte first, like in a hexdump of memory.
+    Le,
+}
+
+/// Radix in which values are written.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum Radix {
+    #[default]
+    Dec,
+    Hex,
+    Bin,
+}
+
+impl Radix {
+    /// Formats a value of the given bit width. Hexadecimal and binary values are zero-padded to
+    /// the bit width.
+    pub fn format(self, value: u64, bits: usize) -> String {
+        match self {
+            Radix::Dec => value.to_string(),
+            Radix::Hex => format!("0x{:0w$x}", value, w = bits.div_ceil(4)),
+            Radix::Bin => format!("{:0w$b}", value, w = bits),
+        }
+    }
+}
+
+/// Signal whose values are shown as fixed-point numbers, given as '<name>:<fractional bits>'.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FixedPoint {
+    pub name: String,
+    pub frac_bits: u32,
+}
+
+impl FromStr for FixedPoint {
+    type Err = String;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let (name, frac_bits) = input
+            .rsplit_once(':')
+            .ok_or_else(|| format!("expected '<name>:<fractional bits>', got '{input}'"))?;
+
+        let frac_bits = frac_bits
+            .parse()
+            .ok()
+            .filter(|bits| *bits <= 64)
+            .ok_or_else(|| format!("'{frac_bits}' is not a number of bits between 0 and 64"))?;
+
+        Ok(Self {
+            name: name.to_string(),
+            frac_bits,
+        })
+    }
+}
+
+/// Substitution of signal names, given like in sed as 's/<regex>/<replacement>/', optionally with
+/// the 'g' flag to replace all matches. Any character after the 's' can be used as delimiter.
+#[derive(Debug, Clone)]
+pub struct Rename {
+    regex: Regex,
+    replacement: String,
+    global: bool,
+}
+
+impl Rename {
+    /// Applies the substitution to the name.
+    pub fn apply(&self, name: &str) -> String {
+        self.regex.replace(name, &self.replacement, self.global)
+    }
+}
+
+impl FromStr for Rename {
+    type Err = String;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let usage = || format!("expected 's/<regex>/<replacement>/[g]', got '{input}'");
+
+        let mut chars = input.chars();
+        if chars.next() != Some('s') {
+            return Err(usage());
+        }
+        let delimiter = chars.next().ok_or_else(usage)?;
+
+        // parts are separated by unescaped delimiters, an escaped delimiter is part of the text
+        let mut parts = Vec::new();
+        let mut part = String::new();
+        while let Some(c) = chars.next() {
+            match c {
+                '\\' => match chars.next() {
+                    Some(next) if next == delimiter => part.push(next),
+                    Some(next) => part.extend(['\\', next]),
+                    None => part.push('\\'),
+                },
+                c if c == delimiter => parts.push(std::mem::take(&mut part)),
+                c => part.push(c),
+            }
+        }
+        parts.push(part);
+
+        let [pattern, replacement, flags] = <[String; 3]>::try_from(parts).map_err(|_| usage())?;
+
+        let global = match flags.as_str() {
+            "" => false,
+            "g" => true,
+            flags => return Err(format!("unknown flags '{flags}', only 'g' is supported")),
+        };
+
+        let regex =
+            Regex::new(&pattern).map_err(|err| format!("invalid regex '{pattern}': {err}"))?;
+
+        Ok(Self {
+            regex,
+            replacement,
+            global,
+        })
+    }
+}
+
+/// Options that control how a parsed witness is analyzed and reported.
+///
+/// The options that print something else instead of the flows, such as '--table' or '--count',
+/// form the 'mode' group, of which at most one can be given.
+#[derive(Debug, Default, Clone, Args)]
+#[command(group(
+    ArgGroup::new("mode")
+        .multiple(false)
+        .args([
+            "count",
+            "expect",
+            "signal_stats",
+            "longest_stable",
+            "table",
+            "events",
+            "init_only",
+            "histogram",
+            "oscillating",
+            "first_change",
+            "merge_flows",
+            "split_dir",
+        ])
+))]
+pub struct ReportOptions {
+    /// Enable additional consistency checks on the witness and the BTOR2 model. Inconsistencies
+    /// that would otherwise produce misleading output are reported as errors.
+    #[arg(long)]
+    pub strict: bool,
+
+    /// Only print the number of frames, input and state assignments and distinct signals in the
+    /// witness, and the sum of the bit widths of these signals.
+    #[arg(long, visible_alias = "stats")]
+    pub count: bool,
+
+    /// Print the line of the BTOR2 model that declares each satisfied property.
+    #[arg(long, requires = "model")]
+    pub show_source: bool,
+
+    /// List the outputs declared in the BTOR2 model after the properties. Outputs of an input or
+    /// state show the last value of that signal in the witness.
+    #[arg(long, requires = "model")]
+    pub show_outputs: bool,
+
+    /// List the steps at which the witness has an input or state part without any assignments
+    /// after the properties. Steps that are missing from the witness are not listed.
+    #[arg(long)]
+    pub show_empty: bool,
+
+    /// Template for each line of a signal's flow. Supported placeholders are '{step}', '{name}',
+    /// '{dec}', '{hex}', '{bin}' and '{delta}'. Any other text is printed as is.
+    #[arg(long, default_value = super::template::DEFAULT_FLOW_TEMPLATE)]
+    pub format_template: FlowTemplate,
+
+    /// Show at most this many bits of binary values and the corresponding number of hex digits.
+    /// The middle of wider values is replaced by '...'.
+    #[arg(long, value_name = "N")]
+    pub max_bits: Option<usize>,
+
+    /// Order of the bits in binary values. For arrays, both the index and the value are written in
+    /// this order.
+    #[arg(long, value_enum, default_value_t = BitOrder::Msb)]
+    pub bit_order: BitOrder,
+
+    /// Order of the bytes in hexadecimal values of the flows, e.g. for comparing with hexdumps of
+    /// memory. The bits within each byte keep their order. Values of less than 9 bits are not
+    /// affected.
+    #[arg(long, value_enum, default_value_t = ByteOrder::Be)]
+    pub byte_order: ByteOrder,
+
+    /// Show the decimal values of the signal as unsigned fixed-point numbers with the given number
+    /// of fractional bits, e.g. 'acc:8'. Can be repeated.
+    #[arg(long, value_name = "NAME:BITS")]
+    pub fixed_point: Vec<FixedPoint>,
+
+    /// Don't print the values of all indices assigned so far below each assignment of an array.
+    /// By default, arrays are shown as sparse maps like '{0: 1, 3: 5}', with only the indices
+    /// that were assigned up to that step.
+    #[arg(long)]
+    pub no_sparse_arrays: bool,
+
+    /// Only keep the values of the first N distinct indices in the sparse map of each array.
+    /// Further indices are counted and noted as '... and M more entries'.
+    #[arg(long, value_name = "N", conflicts_with = "no_sparse_arrays")]
+    pub max_array_entries: Option<usize>,
+
+    /// Print the flow of each signal backwards, from the end of the witness to the first
+    /// assignment.
+    #[arg(long)]
+    pub reverse: bool,
+
+    /// Do not print the 'end' line after the flow of each signal.
+    #[arg(long)]
+    pub no_end_marker: bool,
+
+    /// Below each change of a bitvector, print its previous and new value in binary and mark the
+    /// bits that flipped with '^'.
+    #[arg(long)]
+    pub bit_diff: bool,
+
+    /// Format of the report. The Markdown format writes the properties and the flow of each
+    /// signal as tables.
+    #[arg(
+        long,
+        value_enum,
+        default_value_t = OutputFormat::Text,
+        conflicts_with = "mode"
+    )]
+    pub format: OutputFormat,
+
+    /// Compare the flows of the witness with the expected flows in the file, written with
+    /// '--format json', instead of printing the report. The differences are printed and
+    /// periscope exits with an error if there are any.
+    #[arg(long, value_name = "FILE")]
+    pub expect: Option<PathBuf>,
+
+    /// Radix of the values in Markdown tables.
+    #[arg(long, value_enum, default_value_t = Radix::Dec)]
+    pub radix: Radix,
+
+    /// Print how many assignments had no symbol. Such signals are named by their position in the
+    /// witness, e.g. '#unnamed-@2' for an input and '#unnamed-#2' for a state.
+    #[arg(long)]
+    pub warn_unnamed: bool,
+
+    /// Print the first, last, smallest and largest value of each signal instead of its flow. For
+    /// arrays, the statistics are computed over the assigned values.
+    #[arg(long)]
+    pub signal_stats: bool,
+
+    /// Print the longest interval of steps in which each signal kept a single value instead of
+    /// its flow. Signals that stay unchanged the longest are printed first.
+    #[arg(long)]
+    pub longest_stable: bool,
+
+    /// Print a table with one row per step and one column per signal instead of the flows. The
+    /// value of a signal is carried forward until it changes.
+    #[arg(long)]
+    pub table: bool,
+
+    /// Print a log of the witness instead of the flows: for each step, the inputs and states that
+    /// changed and the properties that are satisfied at that step.
+    #[arg(long)]
+    pub events: bool,
+
+    /// Print only the states assigned in the first frame of the witness, i.e. the initial state,
+    /// instead of the flows.
+    #[arg(long, conflicts_with = "top")]
+    pub init_only: bool,
+
+    /// Print how many steps the signal held each of its values as a bar chart instead of the
+    /// flows. The values that were held the longest are printed first.
+    #[arg(long, value_name = "SIGNAL")]
+    pub histogram: Option<String>,
+
+    /// Print the signals that alternate between two values at a fixed period instead of the flows,
+    /// with the period and how often the values repeated.
+    #[arg(long)]
+    pub oscillating: bool,
+
+    /// Print only the initial value of each signal and the first step at which it changed instead
+    /// of the flows.
+    #[arg(long)]
+    pub first_change: bool,
+
+    /// Only show the steps at which a signal of the table changes and N steps before and after
+    /// them. Runs of other steps are collapsed into a single line.
+    #[arg(long, value_name = "N", requires = "table")]
+    pub context: Option<u64>,
+
+    /// Print the inputs and states in a single flow ordered by signal name instead of two separate
+    /// flows. Each signal is tagged with '@' if it is an input and '#' if it is a state, which
+    /// also tells apart an input and a state of the same name.
+    #[arg(long)]
+    pub merge_flows: bool,
+
+    /// Signal that is shown as a column of the table. Can be repeated, all signals are shown by
+    /// default.
+    #[arg(long, requires = "table")]
+    pub signal: Vec<String>,
+
+    /// Instead of printing the flows, write the flow of each signal into its own file
+    /// '<split-dir>/<signal name>.txt'.
+    #[arg(long)]
+    pub split_dir: Option<PathBuf>,
+
+    /// Only collect the flows from the frame at this zero-based position in the witness on. Unlike
+    /// step numbers, frame positions count every frame, e.g. including the initial '#0' frame.
+    /// Positions past the last frame select only the last frame.
+    #[arg(long, value_name = "INDEX")]
+    pub from_frame: Option<usize>,
+
+    /// Only collect the flows up to and including the frame at this zero-based position. Positions
+    /// past the last frame select the frames up to the end of the witness.
+    #[arg(long, value_name = "INDEX")]
+    pub to_frame: Option<usize>,
+
+    /// Rename signals with a sed-like substitution such as 's/core-0-//' before their flows are
+    /// collected. Can be repeated, the substitutions are applied in order. Signals that end up
+    /// with the same name are merged into one flow. The regex syntax is that of 'sed -E' and
+    /// '\1' to '\9' in the replacement stand for its groups.
+    #[arg(long, value_name = "s/REGEX/REPLACEMENT/")]
+    pub rename: Vec<Rename>,
+
+    /// Only report the inputs and states that the satisfied properties depend on in the BTOR2
+    /// model, directly or through the initial and next values of states.
+    #[arg(long, requires = "model")]
+    pub cone_of_influence: bool,
+
+    /// Only report the N inputs and states that changed most often. Signals with the same number
+    /// of changes are selected by name.
+    #[arg(long, value_name = "N")]
+    pub top: Option<usize>,
+
+    /// Exit with an error after the report if a counterexample has more than N steps, e.g. to
+    /// notice in CI that a change of the model made the counterexample longer.
+    #[arg(long, value_name = "N")]
+    pub assert_steps: Option<usize>,
+
+    /// Exit with an error after the report if the witness contains a counterexample, i.e. if
+    /// btormc did not report 'unsat'.
+    #[arg(long, conflicts_with = "assert_steps")]
+    pub assert_unsat: bool,
+
+    /// Do not analyze and print the flow of inputs.
+    #[arg(long, conflicts_with = "no_state")]
+    pub no_input: bool,
+
+    /// Do not analyze and print the flow of states.
+    #[arg(long)]
+    pub no_state: bool,
+
+    /// Only list properties of the given kind in the report header. Can be repeated.
+    #[arg(long, value_enum)]
+    pub only_kind: Vec<PropKind>,
+
+    /// Do not list properties of the given kind in the report header. Can be repeated.
+    #[arg(long, value_enum)]
+    pub skip_kind: Vec<PropKind>,
+}
+
+impl ReportOptions {
+    /// Whether arrays are shown as sparse maps of their assigned indices, see
+    /// `no_sparse_arrays`.
+    pub fn sparse_arrays(&self) -> bool {
+        !self.no_sparse_arrays
+    }
+
+    /// Whether properties of the given kind should be listed in the report header.
+    pub fn shows_kind(&self, kind: PropKind) -> bool {
+        (self.only_kind.is_empty() || self.only_kind.contains(&kind))
+            && !self.skip_kind.contains(&kind)
+    }
+}
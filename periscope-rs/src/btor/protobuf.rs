@@ -0,0 +1,235 @@
+use std::io::Write;
+
+use super::{
+    assignment::AssignmentKind,
+    flow::{Flow, FlowType},
+    PropKind, PropVec,
+};
+
+/// Writes the properties and flows as a `Witness` message of `proto/witness.proto`.
+pub(crate) fn write_witness(
+    out: &mut dyn Write,
+    props: &[(PropVec, usize)],
+    flows: &[Flow],
+) -> std::io::Result<()> {
+    let mut witness = Message::default();
+
+    for (props, steps) in props {
+        let mut satisfied = Message::default();
+        satisfied.uint(1, *steps as u64);
+
+        for prop in &props.inner {
+            let mut property = Message::default();
+            property.uint(
+                1,
+                match prop.kind {
+                    PropKind::Bad => 0,
+                    PropKind::Justice => 1,
+                    PropKind::Constraint => 2,
+                },
+            );
+            property.uint(2, prop.idx);
+
+            if let Some(source) = &prop.property {
+                property.string(3, source.name.as_deref().unwrap_or_default());
+                property.uint(4, source.node as u64);
+            }
+
+            satisfied.message(2, property);
+        }
+
+        witness.message(1, satisfied);
+    }
+
+    for flow in flows {
+        let mut flow_message = Message::default();
+        flow_message.uint(
+            1,
+            match flow.flow_type() {
+                FlowType::Input => 0,
+                FlowType::State => 1,
+            },
+        );
+
+        for (name, assignments) in flow.signals() {
+            let mut signal = Message::default();
+            signal.string(1, name);
+
+            for (step, assignment) in assignments {
+                let mut change = Message::default();
+                change.uint(1, *step);
+
+                match assignment.kind {
+                    AssignmentKind::BitVec { value, bits } => {
+                        change.uint(2, value);
+                        change.uint(3, bits as u64);
+                    }
+                    AssignmentKind::Array {
+                        index,
+                        index_bits,
+                        value,
+                        bits,
+                    } => {
+                        change.uint(2, value);
+                        change.uint(3, bits as u64);
+                        // explicitly present, so that index 0 is distinguishable from bitvectors
+                        change.tag(4, WireType::Varint);
+                        change.varint(index);
+                        change.uint(5, index_bits as u64);
+                    }
+                }
+
+                signal.message(2, change);
+            }
+
+            flow_message.message(2, signal);
+        }
+
+        witness.message(2, flow_message);
+    }
+
+    out.write_all(&witness.buf)
+}
+
+#[derive(Clone, Copy)]
+enum WireType {
+    Varint = 0,
+    Len = 2,
+}
+
+/// Encoded fields of a protobuf message. Fields with default values are omitted, as in proto3.
+#[derive(Default)]
+struct Message {
+    buf: Vec<u8>,
+}
+
+impl Message {
+    fn uint(&mut self, field: u32, value: u64) {
+        if value != 0 {
+            self.tag(field, WireType::Varint);
+            self.varint(value);
+        }
+    }
+
+    fn string(&mut self, field: u32, value: &str) {
+        if !value.is_empty() {
+            self.bytes(field, value.as_bytes());
+        }
+    }
+
+    /// Nested messages are always written, even if empty, so that repeated fields keep their
+    /// number of elements.
+    fn message(&mut self, field: u32, message: Message) {
+        self.bytes(field, &message.buf);
+    }
+
+    fn bytes(&mut self, field: u32, bytes: &[u8]) {
+        self.tag(field, WireType::Len);
+        self.varint(bytes.len() as u64);
+        self.buf.extend_from_slice(bytes);
+    }
+
+    fn tag(&mut self, field: u32, wire_type: WireType) {
+        self.varint(u64::from(field) << 3 | wire_type as u64);
+    }
+
+    fn varint(&mut self, mut value: u64) {
+        while value >= 0x80 {
+            self.buf.push((value as u8 & 0x7f) | 0x80);
+            value >>= 7;
+        }
+
+        self.buf.push(value as u8);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::{BTreeMap, HashMap};
+
+    use super::*;
+    use crate::btor::{assignment::Assignment, witness_format::Prop};
+
+    #[test]
+    fn varints_use_seven_bits_per_byte() {
+        let mut message = Message::default();
+        message.uint(1, 1);
+        message.uint(2, 0);
+        message.uint(3, 300);
+        message.uint(4, u64::MAX);
+
+        assert_eq!(
+            message.buf,
+            [
+                0x08, 0x01, // field 1
+                0x18, 0xac, 0x02, // field 3, field 2 is omitted
+                0x20, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x01,
+            ]
+        );
+    }
+
+    #[test]
+    fn witness_is_encoded() {
+        let props = [(
+            PropVec {
+                inner: vec![Prop {
+                    kind: PropKind::Bad,
+                    idx: 0,
+                    property: None,
+                    block: None,
+                }],
+            },
+            300,
+        )];
+
+        let assignment = Assignment {
+            kind: AssignmentKind::BitVec { value: 5, bits: 3 },
+            symbol: None,
+            index: 0,
+        };
+
+        let flows = [
+            Flow::new(
+                FlowType::Input,
+                BTreeMap::from([(String::from("a"), vec![(0, assignment)])]),
+                0,
+                HashMap::new(),
+                BTreeMap::new(),
+            ),
+            // no signals, the repeated field is empty
+            Flow::new(
+                FlowType::State,
+                BTreeMap::new(),
+                0,
+                HashMap::new(),
+                BTreeMap::new(),
+            ),
+        ];
+
+        let mut out = Vec::new();
+        write_witness(&mut out, &props, &flows).expect("witness is written");
+
+        assert_eq!(
+            out,
+            [
+                // satisfied properties, steps is a multi-byte varint
+                0x0a, 0x05, 0x08, 0xac, 0x02, //
+                // property with only default values
+                0x12, 0x00, //
+                // input flow with signal "a"
+                0x12, 0x0b, 0x12, 0x09, 0x0a, 0x01, b'a', 0x12, 0x04, 0x10, 0x05, 0x18,
+                0x03, //
+                // state flow without signals
+                0x12, 0x02, 0x08, 0x01,
+            ]
+        );
+    }
+
+    #[test]
+    fn empty_witness_is_empty_message() {
+        let mut out = Vec::new();
+        write_witness(&mut out, &[], &[]).expect("witness is written");
+
+        assert!(out.is_empty());
+    }
+}
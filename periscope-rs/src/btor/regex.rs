@@ -0,0 +1,504 @@
+//! Small backtracking regular expression engine for renaming signals. It supports the extended
+//! syntax of `sed -E` that is useful for names: literals, `.`, sets like `[a-z]` or `[^0-9]`, the
+//! classes `\d`, `\w`, `\s` and their negations, anchors `^` and `$`, groups `(...)`,
+//! alternatives `|` and the greedy quantifiers `*`, `+`, `?` and `{n,m}`.
+
+use std::fmt;
+
+/// Compiled regular expression.
+#[derive(Debug, Clone)]
+pub(super) struct Regex {
+    node: Node,
+    groups: usize,
+}
+
+/// Span of the whole match and of each group, `None` for groups that didn't take part.
+type Captures = Vec<Option<(usize, usize)>>;
+
+#[derive(Debug, Clone)]
+enum Node {
+    Char(char),
+    Any,
+    Set {
+        ranges: Vec<(char, char)>,
+        negated: bool,
+    },
+    Start,
+    End,
+    Group(Box<Node>, usize),
+    Concat(Vec<Node>),
+    Alternation(Vec<Node>),
+    Repeat {
+        node: Box<Node>,
+        min: usize,
+        max: Option<usize>,
+    },
+}
+
+/// Error in the syntax of a regular expression.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) struct RegexError(String);
+
+impl fmt::Display for RegexError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl Regex {
+    pub(super) fn new(pattern: &str) -> Result<Self, RegexError> {
+        let mut parser = Parser {
+            chars: pattern.chars().collect(),
+            pos: 0,
+            groups: 0,
+        };
+
+        let node = parser.alternation()?;
+
+        match parser.peek() {
+            None => Ok(Self {
+                node,
+                groups: parser.groups,
+            }),
+            Some(')') => Err(RegexError(String::from("unmatched ')'"))),
+            Some(c) => Err(RegexError(format!("unexpected '{c}'"))),
+        }
+    }
+
+    /// Finds the leftmost match in `input` starting at or after `from`. Returns the spans of the
+    /// match and its groups in characters of `input`, the whole match first.
+    fn find_at(&self, input: &[char], from: usize) -> Option<Captures> {
+        (from..=input.len()).find_map(|start| {
+            let mut captures = vec![None; self.groups + 1];
+            let mut end = None;
+
+            let matched = match_node(&self.node, input, start, &mut captures, &mut |pos, _| {
+                end = Some(pos);
+                true
+            });
+
+            // captures are restored on backtracking, so the groups of the match are still set
+            matched.then(|| {
+                captures[0] = end.map(|end| (start, end));
+                captures
+            })
+        })
+    }
+
+    /// Replaces the first match, or all matches if `global` is set, with the replacement. In the
+    /// replacement, `&` stands for the whole match and `\1` to `\9` for the groups. `\&` and `\\`
+    /// are a literal `&` and `\`.
+    pub(super) fn replace(&self, input: &str, replacement: &str, global: bool) -> String {
+        let chars: Vec<char> = input.chars().collect();
+        let mut result = String::new();
+        let mut pos = 0;
+
+        while let Some(captures) = self.find_at(&chars, pos) {
+            let Some((start, end)) = captures[0] else {
+                break;
+            };
+
+            result.extend(&chars[pos..start]);
+            expand(&mut result, replacement, &chars, &captures);
+
+            pos = end;
+
+            // an empty match is followed by the next character, so that matching advances
+            if start == end {
+                match chars.get(end) {
+                    Some(c) => result.push(*c),
+                    None => return result,
+                }
+                pos += 1;
+            }
+
+            if !global || pos > chars.len() {
+                break;
+            }
+        }
+
+        result.extend(chars.get(pos..).unwrap_or_default());
+        result
+    }
+}
+
+fn expand(result: &mut String, replacement: &str, input: &[char], captures: &Captures) {
+    let mut chars = replacement.chars();
+
+    let push_group = |result: &mut String, group: usize| {
+        if let Some(Some((start, end))) = captures.get(group) {
+            result.extend(&input[*start..*end]);
+        }
+    };
+
+    while let Some(c) = chars.next() {
+        match c {
+            '&' => push_group(result, 0),
+            '\\' => match chars.next() {
+                Some(digit @ '0'..='9') => {
+                    push_group(result, digit.to_digit(10).unwrap_or_default() as usize)
+                }
+                Some(c) => result.push(c),
+                None => result.push('\\'),
+            },
+            c => result.push(c),
+        }
+    }
+}
+
+/// Matches the node at `pos` and calls `next` with the end of each possible match until `next`
+/// accepts one. Captures set by rejected matches are restored.
+fn match_node(
+    node: &Node,
+    input: &[char],
+    pos: usize,
+    captures: &mut Captures,
+    next: &mut dyn FnMut(usize, &mut Captures) -> bool,
+) -> bool {
+    match node {
+        Node::Char(expected) => input.get(pos) == Some(expected) && next(pos + 1, captures),
+        Node::Any => pos < input.len() && next(pos + 1, captures),
+        Node::Set { ranges, negated } => match input.get(pos) {
+            Some(c) => {
+                let found = ranges.iter().any(|(from, to)| (from..=to).contains(&c));
+                found != *negated && next(pos + 1, captures)
+            }
+            None => false,
+        },
+        Node::Start => pos == 0 && next(pos, captures),
+        Node::End => pos == input.len() && next(pos, captures),
+        Node::Group(inner, group) => {
+            match_node(inner, input, pos, captures, &mut |end, captures| {
+                let previous = captures[*group];
+                captures[*group] = Some((pos, end));
+
+                if next(end, captures) {
+                    return true;
+                }
+
+                captures[*group] = previous;
+                false
+            })
+        }
+        Node::Concat(nodes) => match_sequence(nodes, input, pos, captures, next),
+        Node::Alternation(alternatives) => alternatives
+            .iter()
+            .any(|alternative| match_node(alternative, input, pos, captures, next)),
+        Node::Repeat { .. } => match_repeat(node, 0, input, pos, captures, next),
+    }
+}
+
+fn match_sequence(
+    nodes: &[Node],
+    input: &[char],
+    pos: usize,
+    captures: &mut Captures,
+    next: &mut dyn FnMut(usize, &mut Captures) -> bool,
+) -> bool {
+    match nodes.split_first() {
+        None => next(pos, captures),
+        Some((first, rest)) => match_node(first, input, pos, captures, &mut |end, captures| {
+            match_sequence(rest, input, end, captures, next)
+        }),
+    }
+}
+
+/// Greedily matches the node of the repetition as often as possible, then backtracks to fewer
+/// repetitions. `count` is the number of repetitions matched so far.
+fn match_repeat(
+    repeat: &Node,
+    count: usize,
+    input: &[char],
+    pos: usize,
+    captures: &mut Captures,
+    next: &mut dyn FnMut(usize, &mut Captures) -> bool,
+) -> bool {
+    let Node::Repeat { node, min, max } = repeat else {
+        return match_node(repeat, input, pos, captures, next);
+    };
+
+    if max.is_none_or(|max| count < max) {
+        let repeated = match_node(node, input, pos, captures, &mut |end, captures| {
+            // repetitions that match nothing would repeat forever
+            if end == pos && count >= *min {
+                return false;
+            }
+
+            match_repeat(repeat, count + 1, input, end, captures, next)
+        });
+
+        if repeated {
+            return true;
+        }
+    }
+
+    count >= *min && next(pos, captures)
+}
+
+struct Parser {
+    chars: Vec<char>,
+    pos: usize,
+    groups: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.pos += 1;
+        Some(c)
+    }
+
+    fn alternation(&mut self) -> Result<Node, RegexError> {
+        let mut alternatives = vec![self.concat()?];
+
+        while self.peek() == Some('|') {
+            self.pos += 1;
+            alternatives.push(self.concat()?);
+        }
+
+        Ok(match alternatives.len() {
+            1 => alternatives.remove(0),
+            _ => Node::Alternation(alternatives),
+        })
+    }
+
+    fn concat(&mut self) -> Result<Node, RegexError> {
+        let mut nodes = Vec::new();
+
+        while let Some(c) = self.peek() {
+            if c == '|' || c == ')' {
+                break;
+            }
+
+            let atom = self.atom()?;
+            nodes.push(self.quantified(atom)?);
+        }
+
+        Ok(Node::Concat(nodes))
+    }
+
+    fn atom(&mut self) -> Result<Node, RegexError> {
+        let c = self
+            .bump()
+            .ok_or_else(|| RegexError(String::from("unexpected end")))?;
+
+        Ok(match c {
+            '.' => Node::Any,
+            '^' => Node::Start,
+            '$' => Node::End,
+            '(' => {
+                self.groups += 1;
+                let group = self.groups;
+                let inner = self.alternation()?;
+
+                if self.bump() != Some(')') {
+                    return Err(RegexError(String::from("unclosed '('")));
+                }
+
+                Node::Group(Box::new(inner), group)
+            }
+            '[' => self.set()?,
+            '\\' => self.escape()?,
+            '*' | '+' | '?' => {
+                return Err(RegexError(format!(
+                    "'{c}' does not follow anything to repeat"
+                )))
+            }
+            c => Node::Char(c),
+        })
+    }
+
+    fn escape(&mut self) -> Result<Node, RegexError> {
+        let c = self
+            .bump()
+            .ok_or_else(|| RegexError(String::from("trailing '\\'")))?;
+
+        let (ranges, negated) = match c {
+            'd' | 'D' => (vec![('0', '9')], c == 'D'),
+            'w' | 'W' => (
+                vec![('a', 'z'), ('A', 'Z'), ('0', '9'), ('_', '_')],
+                c == 'W',
+            ),
+            's' | 'S' => (
+                vec![(' ', ' '), ('\t', '\t'), ('\n', '\n'), ('\r', '\r')],
+                c == 'S',
+            ),
+            'n' => return Ok(Node::Char('\n')),
+            't' => return Ok(Node::Char('\t')),
+            c => return Ok(Node::Char(c)),
+        };
+
+        Ok(Node::Set { ranges, negated })
+    }
+
+    /// Parses a set after the opening `[`. A `]` right at the start is part of the set.
+    fn set(&mut self) -> Result<Node, RegexError> {
+        let negated = self.peek() == Some('^');
+        if negated {
+            self.pos += 1;
+        }
+
+        let mut ranges = Vec::new();
+        let mut first = true;
+
+        loop {
+            let c = self
+                .bump()
+                .ok_or_else(|| RegexError(String::from("unclosed '['")))?;
+
+            match c {
+                ']' if !first => break,
+                '\\' => {
+                    let escaped = self
+                        .bump()
+                        .ok_or_else(|| RegexError(String::from("unclosed '['")))?;
+                    ranges.push((escaped, escaped));
+                }
+                c => {
+                    let is_range = self.peek() == Some('-')
+                        && self.chars.get(self.pos + 1).is_some_and(|end| *end != ']');
+
+                    if is_range {
+                        let end = self.chars[self.pos + 1];
+                        self.pos += 2;
+
+                        if end < c {
+                            return Err(RegexError(format!("invalid range '{c}-{end}'")));
+                        }
+
+                        ranges.push((c, end));
+                    } else {
+                        ranges.push((c, c));
+                    }
+                }
+            }
+
+            first = false;
+        }
+
+        Ok(Node::Set { ranges, negated })
+    }
+
+    fn quantified(&mut self, atom: Node) -> Result<Node, RegexError> {
+        let (min, max) = match self.peek() {
+            Some('*') => (0, None),
+            Some('+') => (1, None),
+            Some('?') => (0, Some(1)),
+            Some('{') => return self.counted(atom),
+            _ => return Ok(atom),
+        };
+
+        self.pos += 1;
+
+        Ok(Node::Repeat {
+            node: Box::new(atom),
+            min,
+            max,
+        })
+    }
+
+    /// Parses `{n}`, `{n,}` or `{n,m}` after the atom. Braces that don't form a count are
+    /// matched literally.
+    fn counted(&mut self, atom: Node) -> Result<Node, RegexError> {
+        let Some(len) = self.chars[self.pos..].iter().position(|c| *c == '}') else {
+            return Ok(atom);
+        };
+
+        let count: String = self.chars[self.pos + 1..self.pos + len].iter().collect();
+        let parse = |n: &str| n.parse::<usize>().ok();
+
+        let (min, max) = match count.split_once(',') {
+            None => match parse(&count) {
+                Some(n) => (n, Some(n)),
+                None => return Ok(atom),
+            },
+            Some((min, "")) => match parse(min) {
+                Some(min) => (min, None),
+                None => return Ok(atom),
+            },
+            Some((min, max)) => match (parse(min), parse(max)) {
+                (Some(min), Some(max)) if min <= max => (min, Some(max)),
+                (Some(_), Some(_)) => {
+                    return Err(RegexError(format!("invalid count '{{{count}}}'")))
+                }
+                _ => return Ok(atom),
+            },
+        };
+
+        self.pos += len + 1;
+
+        Ok(Node::Repeat {
+            node: Box::new(atom),
+            min,
+            max,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn replace(pattern: &str, input: &str, replacement: &str, global: bool) -> String {
+        Regex::new(pattern)
+            .expect("pattern is valid")
+            .replace(input, replacement, global)
+    }
+
+    #[test]
+    fn literals_and_classes() {
+        assert_eq!(replace("core-0-", "core-0-pc", "", false), "pc");
+        assert_eq!(replace(r"\d+", "reg12x", "N", false), "regNx");
+        assert_eq!(replace("[a-c]", "abcd", "_", true), "___d");
+        assert_eq!(replace("[^0-9]", "a1b2", "", true), "12");
+        assert_eq!(replace("x", "abc", "y", true), "abc");
+    }
+
+    #[test]
+    fn anchors() {
+        assert_eq!(replace("^a", "aaa", "b", true), "baa");
+        assert_eq!(replace("a$", "aaa", "b", true), "aab");
+        assert_eq!(replace("^$", "", "empty", false), "empty");
+        assert_eq!(replace("^b", "ab", "x", false), "ab");
+    }
+
+    #[test]
+    fn groups_and_backreferences() {
+        assert_eq!(replace(r"(\w+)-(\d)", "core-0", r"\2-\1", false), "0-core");
+        assert_eq!(replace("a(b)?c", "ac", r"[\1]", false), "[]");
+        assert_eq!(replace("(a|bc)+", "xbcay", "<&>", false), "x<bca>y");
+        assert_eq!(replace("a", "a", r"\&\\", false), r"&\");
+        assert_eq!(replace("a{2,3}", "aaaa", "b", true), "ba");
+    }
+
+    #[test]
+    fn star_on_empty_group_terminates() {
+        assert_eq!(replace("()*", "ab", "-", true), "-a-b-");
+        assert_eq!(replace("(a*)*b", "aab", "x", false), "x");
+        assert_eq!(replace("(|a)+", "aa", "x", false), "x");
+    }
+
+    #[test]
+    fn global_replacement_with_empty_matches() {
+        assert_eq!(replace("x*", "abc", "-", true), "-a-b-c-");
+        assert_eq!(replace("x*", "abc", "-", false), "-abc");
+        assert_eq!(replace("b*", "abba", "-", true), "-a--a-");
+    }
+
+    #[test]
+    fn invalid_patterns_are_rejected() {
+        let error = |pattern| Regex::new(pattern).unwrap_err().to_string();
+
+        assert_eq!(error("(a"), "unclosed '('");
+        assert_eq!(error("a)"), "unmatched ')'");
+        assert_eq!(error("[a"), "unclosed '['");
+        assert_eq!(error("[z-a]"), "invalid range 'z-a'");
+        assert_eq!(error("*a"), "'*' does not follow anything to repeat");
+        assert_eq!(error("a{3,1}"), "invalid count '{3,1}'");
+        assert_eq!(error("a\\"), "trailing '\\'");
+    }
+}
@@ -0,0 +1,296 @@
+use std::{fmt::Write, str::FromStr};
+
+use super::{
+    assignment::Assignment,
+    helpers,
+    options::{BitOrder, ByteOrder},
+};
+
+/// Template that reproduces the default output of the flow report.
+pub const DEFAULT_FLOW_TEMPLATE: &str = "{step}: {dec} ({bin})";
+
+/// Template used for rendering a single assignment line in the flow report.
+///
+/// Supported placeholders are `{step}`, `{name}`, `{dec}`, `{hex}`, `{bin}` and `{delta}`. Any
+/// other text, including unknown placeholders, is kept as is.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FlowTemplate {
+    segments: Vec<Segment>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Segment {
+    Literal(String),
+    Field(Field),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Field {
+    /// Step prefixed with '@' or '#', right-aligned to the widest step.
+    Step,
+    /// Name of the signal.
+    Name,
+    /// Decimal value, right-aligned to the widest value.
+    Dec,
+    /// Hexadecimal value, zero-padded to the bit width.
+    Hex,
+    /// Binary value.
+    Bin,
+    /// Signed difference to the previous value of the signal, empty for the first value.
+    Delta,
+}
+
+impl Field {
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "step" => Some(Self::Step),
+            "name" => Some(Self::Name),
+            "dec" => Some(Self::Dec),
+            "hex" => Some(Self::Hex),
+            "bin" => Some(Self::Bin),
+            "delta" => Some(Self::Delta),
+            _ => None,
+        }
+    }
+}
+
+/// Everything needed to render one line of a signal's flow.
+pub struct FlowLine<'a> {
+    pub prefix: &'a str,
+    pub step: u64,
+    pub step_width: usize,
+    pub name: &'a str,
+    pub assignment: &'a Assignment,
+    pub value_width: usize,
+    pub previous: Option<&'a Assignment>,
+    /// Values wider than this are elided in the middle, see `--max-bits`.
+    pub max_bits: Option<usize>,
+    /// Order of the bits of binary values, see `--bit-order`.
+    pub bit_order: BitOrder,
+    /// Order of the bytes of hexadecimal values, see `--byte-order`.
+    pub byte_order: ByteOrder,
+    /// Decimal values are shown as fixed-point numbers with this many fractional bits, see
+    /// `--fixed-point`.
+    pub frac_bits: Option<u32>,
+}
+
+impl FlowTemplate {
+    pub fn render(&self, line: &FlowLine) -> String {
+        let mut buf = String::new();
+
+        for segment in &self.segments {
+            match segment {
+                Segment::Literal(text) => buf.push_str(text),
+                Segment::Field(field) => {
+                    let _ = Self::render_field(&mut buf, *field, line);
+                }
+            }
+        }
+
+        buf
+    }
+
+    fn render_field(buf: &mut String, field: Field, line: &FlowLine) -> std::fmt::Result {
+        let value = line.assignment.get_value();
+
+        match field {
+            Field::Step => write!(buf, "{}{:>w$}", line.prefix, line.step, w = line.step_width),
+            Field::Name => write!(buf, "{}", line.name),
+            Field::Dec => match line.frac_bits {
+                Some(frac_bits) => write!(
+                    buf,
+                    "{:>w$}",
+                    helpers::fixed_point(value, frac_bits),
+                    w = line.value_width
+                ),
+                None => write!(buf, "{:>w$}", value, w = line.value_width),
+            },
+            Field::Hex => {
+                let bits = line.assignment.kind.bits();
+                let hex = match line.byte_order {
+                    ByteOrder::Le if bits > 8 => value.to_le_bytes()[..bits.div_ceil(8).min(8)]
+                        .iter()
+                        .map(|byte| format!("{byte:02x}"))
+                        .collect(),
+                    _ => format!("{:0w$x}", value, w = bits.div_ceil(4)),
+                };
+
+                match line.max_bits {
+                    Some(max_bits) => {
+                        write!(
+                            buf,
+                            "0x{}",
+                            helpers::elide_middle(&hex, max_bits.div_ceil(4))
+                        )
+                    }
+                    None => write!(buf, "0x{hex}"),
+                }
+            }
+            Field::Bin => write!(
+                buf,
+                "{}",
+                line.assignment
+                    .kind
+                    .to_elided_binary_string(line.max_bits.unwrap_or(usize::MAX), line.bit_order)
+            ),
+            Field::Delta => match line.previous {
+                Some(previous) => {
+                    let delta = i128::from(value) - i128::from(previous.get_value());
+                    write!(buf, "{:+}", delta)
+                }
+                None => Ok(()),
+            },
+        }
+    }
+}
+
+impl Default for FlowTemplate {
+    fn default() -> Self {
+        DEFAULT_FLOW_TEMPLATE
+            .parse()
+            .expect("Default flow template is valid.")
+    }
+}
+
+impl FromStr for FlowTemplate {
+    type Err = String;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let mut segments = Vec::new();
+        let mut literal = String::new();
+        let mut rest = input;
+
+        while let Some(start) = rest.find('{') {
+            literal.push_str(&rest[..start]);
+            rest = &rest[start..];
+
+            let field = rest
+                .find('}')
+                .and_then(|end| Field::from_name(&rest[1..end]).map(|field| (field, end)));
+
+            match field {
+                Some((field, end)) => {
+                    if !literal.is_empty() {
+                        segments.push(Segment::Literal(std::mem::take(&mut literal)));
+                    }
+
+                    segments.push(Segment::Field(field));
+                    rest = &rest[end + 1..];
+                }
+                None => {
+                    literal.push('{');
+                    rest = &rest[1..];
+                }
+            }
+        }
+
+        literal.push_str(rest);
+
+        if !literal.is_empty() {
+            segments.push(Segment::Literal(literal));
+        }
+
+        Ok(FlowTemplate { segments })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::btor::assignment::AssignmentKind;
+
+    fn bitvec(value: u64, bits: usize) -> Assignment {
+        Assignment {
+            kind: AssignmentKind::BitVec { value, bits },
+            symbol: Some(String::from("in")),
+            index: 0,
+        }
+    }
+
+    fn line<'a>(assignment: &'a Assignment, previous: Option<&'a Assignment>) -> FlowLine<'a> {
+        FlowLine {
+            prefix: "@",
+            step: 7,
+            step_width: 3,
+            name: "in",
+            assignment,
+            value_width: 4,
+            previous,
+            max_bits: None,
+            bit_order: BitOrder::Msb,
+            byte_order: ByteOrder::Be,
+            frac_bits: None,
+        }
+    }
+
+    fn render(template: &str, line: &FlowLine) -> String {
+        template
+            .parse::<FlowTemplate>()
+            .expect("template is valid")
+            .render(line)
+    }
+
+    #[test]
+    fn default_template_reproduces_the_flow_line() {
+        let assignment = bitvec(5, 8);
+
+        assert_eq!(
+            FlowTemplate::default().render(&line(&assignment, None)),
+            "@  7:    5 (00000101)"
+        );
+    }
+
+    #[test]
+    fn literals_and_unknown_placeholders_are_kept() {
+        let assignment = bitvec(5, 8);
+        let line = line(&assignment, None);
+
+        assert_eq!(render("value of {name}", &line), "value of in");
+        assert_eq!(render("{foo} {name}", &line), "{foo} in");
+        assert_eq!(render("{name} {", &line), "in {");
+        assert_eq!(render("{{name}}", &line), "{in}");
+        assert_eq!(render("", &line), "");
+    }
+
+    #[test]
+    fn delta_is_signed_and_empty_for_the_first_value() {
+        let first = bitvec(5, 8);
+        let second = bitvec(3, 8);
+        let third = bitvec(10, 8);
+
+        assert_eq!(render("[{delta}]", &line(&first, None)), "[]");
+        assert_eq!(render("{delta}", &line(&second, Some(&first))), "-2");
+        assert_eq!(render("{delta}", &line(&third, Some(&second))), "+7");
+        assert_eq!(render("{delta}", &line(&third, Some(&third))), "+0");
+    }
+
+    #[test]
+    fn hex_is_written_in_both_byte_orders() {
+        let hex = |value, bits, byte_order| {
+            let assignment = bitvec(value, bits);
+            let line = FlowLine {
+                byte_order,
+                ..line(&assignment, None)
+            };
+
+            render("{hex}", &line)
+        };
+
+        assert_eq!(hex(0x1234, 16, ByteOrder::Be), "0x1234");
+        assert_eq!(hex(0x1234, 16, ByteOrder::Le), "0x3412");
+        assert_eq!(hex(0xabc, 12, ByteOrder::Be), "0xabc");
+        assert_eq!(hex(0xabc, 12, ByteOrder::Le), "0xbc0a");
+        assert_eq!(hex(0x0b, 8, ByteOrder::Le), "0x0b");
+    }
+
+    #[test]
+    fn dec_uses_fixed_point_with_frac_bits() {
+        let assignment = bitvec(6, 8);
+        let line = FlowLine {
+            frac_bits: Some(2),
+            ..line(&assignment, None)
+        };
+
+        assert_eq!(render("{dec}", &line), " 1.5");
+    }
+}
@@ -1,14 +1,15 @@
 use std::{fmt::Write, str::FromStr};
 
-use nom::{branch, bytes::complete, character, combinator, multi, sequence};
+use nom::{bytes::complete, character, combinator, multi, sequence};
 use serde::{Deserialize, Serialize};
 
-use super::{assignment::Assignment, btor2::Property, helpers};
+use super::{assignment::Assignment, btor2::Property, helpers, options::ParseOptions};
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, clap::ValueEnum)]
 pub enum PropKind {
     Bad,
     Justice,
+    Constraint,
 }
 
 impl FromStr for PropKind {
@@ -18,13 +19,108 @@ impl FromStr for PropKind {
         match s {
             "bad" => Ok(Self::Bad),
             "justice" => Ok(Self::Justice),
+            "constraint" => Ok(Self::Constraint),
             _ => Err(format!("Unknown prop kind: '{s}'")),
         }
     }
 }
 
+/// Letters that mark the kind of a property in the witness header, e.g. `b` in `b0`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PropTags {
+    tags: Vec<(char, PropKind)>,
+}
+
+impl PropTags {
+    /// Tags used by `btormc`.
+    pub const DEFAULT: &'static str = "b=bad,j=justice";
+
+    fn kind(&self, tag: char) -> Option<PropKind> {
+        self.tags
+            .iter()
+            .find(|(other, _)| *other == tag)
+            .map(|(_, kind)| *kind)
+    }
+
+    /// Tag of the property at the start of `rest` if it is in the witness header, but has no
+    /// kind. `rest` is the remaining part of `input` at which parsing failed.
+    pub(super) fn unknown_tag(&self, input: &str, rest: &str) -> Option<char> {
+        let consumed = &input[..input.len() - rest.len()];
+        let in_header = consumed
+            .rsplit('\n')
+            .nth(1)
+            .is_some_and(|previous| previous.trim() == "sat");
+
+        let mut chars = rest.chars();
+        match (chars.next(), chars.next()) {
+            (Some(tag), Some(digit))
+                if in_header && digit.is_ascii_digit() && self.kind(tag).is_none() =>
+            {
+                Some(tag)
+            }
+            _ => None,
+        }
+    }
+}
+
+impl Default for PropTags {
+    fn default() -> Self {
+        Self {
+            tags: vec![('b', PropKind::Bad), ('j', PropKind::Justice)],
+        }
+    }
+}
+
+impl FromStr for PropTags {
+    type Err = String;
+
+    /// Parses a comma separated list of mappings such as `b=bad,j=justice`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut tags = Vec::new();
+
+        for mapping in s.split(',').map(str::trim).filter(|m| !m.is_empty()) {
+            let (tag, kind) = mapping
+                .split_once('=')
+                .ok_or_else(|| format!("Expected '<letter>=<kind>', got '{mapping}'"))?;
+
+            let mut chars = tag.trim().chars();
+            let tag = match (chars.next(), chars.next()) {
+                (Some(tag), None) if !tag.is_ascii_digit() => tag,
+                _ => {
+                    return Err(format!(
+                        "Tag must be a single non-digit letter, got '{tag}'"
+                    ))
+                }
+            };
+
+            if tags.iter().any(|(other, _)| *other == tag) {
+                return Err(format!("Tag '{tag}' is mapped more than once"));
+            }
+
+            tags.push((tag, parse_tag_kind(kind.trim())?));
+        }
+
+        if tags.is_empty() {
+            return Err(String::from("At least one tag is required"));
+        }
+
+        Ok(Self { tags })
+    }
+}
+
+/// Kind of a property tag. Checkers that name the kinds differently can use the aliases `assert`
+/// for safety properties, which are reported like bad properties, and `fair` or `liveness` for
+/// justice properties.
+fn parse_tag_kind(kind: &str) -> Result<PropKind, String> {
+    match kind {
+        "assert" => Ok(PropKind::Bad),
+        "fair" | "liveness" => Ok(PropKind::Justice),
+        kind => kind.parse(),
+    }
+}
+
 #[repr(transparent)]
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct PropVec {
     pub inner: Vec<Prop>,
 }
@@ -40,10 +136,13 @@ impl PropVec {
                     let property = prop.property.as_ref().unwrap();
                     let _ = write!(
                         &mut prop_string,
-                        " named '{}' with nid: {}",
-                        property.name.as_ref().unwrap(),
-                        property.node
+                        " named '{}'",
+                        property.name.as_ref().unwrap()
                     );
+
+                    if property.node != 0 {
+                        let _ = write!(&mut prop_string, " with nid: {}", property.node);
+                    }
                 }
 
                 prop_string
@@ -58,13 +157,22 @@ pub struct Prop {
     pub kind: PropKind,
     pub idx: u64,
     pub property: Option<Property>,
+    /// Position of the witness block that lists the property, if the witness has more than one
+    /// block. Properties of single-block witnesses are in block 0.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub block: Option<usize>,
 }
 
 impl std::fmt::Display for Prop {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some(block) = self.block {
+            write!(f, "[block {block}] ")?;
+        }
+
         match self.kind {
             PropKind::Bad => write!(f, "Bad at ")?,
             PropKind::Justice => write!(f, "Justice at ")?,
+            PropKind::Constraint => write!(f, "Constraint at ")?,
         };
 
         write!(f, "{}", self.idx)
@@ -72,39 +180,38 @@ impl std::fmt::Display for Prop {
 }
 
 impl Prop {
-    fn parse(input: &str) -> nom::IResult<&str, Self> {
+    pub(super) fn parse<'a>(tags: &PropTags, input: &'a str) -> nom::IResult<&'a str, Self> {
         combinator::map(
             sequence::pair(
-                branch::alt((complete::tag("b"), complete::tag("j"))),
+                combinator::map_opt(character::complete::anychar, |tag| tags.kind(tag)),
                 character::complete::digit1,
             ),
-            |(kind_str, idx_str): (&str, &str)| {
+            |(kind, idx_str): (PropKind, &str)| {
                 let idx = idx_str.parse().expect("digit1 parses only digits.");
-                let kind = match kind_str {
-                    "b" => PropKind::Bad,
-                    "j" => PropKind::Justice,
-                    _ => unreachable!("Parser recognizes only 'j' and 'b' as prop kinds."),
-                };
                 Prop {
                     kind,
                     idx,
                     property: None,
+                    block: None,
                 }
             },
         )(input)
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct WitnessHeader {
     pub props: Vec<Prop>,
 }
 
 impl WitnessHeader {
-    fn parse(input: &str) -> nom::IResult<&str, Self> {
+    fn parse<'a>(tags: &PropTags, input: &'a str) -> nom::IResult<&'a str, Self> {
         combinator::map(
             sequence::terminated(
-                sequence::preceded(complete::tag("sat\n"), multi::many1(Prop::parse)),
+                sequence::preceded(
+                    complete::tag("sat\n"),
+                    multi::many1(|input| Prop::parse(tags, input)),
+                ),
                 helpers::newline,
             ),
             |props| WitnessHeader { props },
@@ -112,7 +219,7 @@ impl WitnessHeader {
     }
 }
 
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct Model {
     pub assignments: Vec<Assignment>,
 }
@@ -136,17 +243,26 @@ impl Model {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Transition {
     pub step: u64,
     pub model: Model,
 }
 
 impl Transition {
-    fn parse(input: &str) -> nom::IResult<&str, Self> {
+    /// Parses the step and the assignments of a transition. If `default_step` is given, the step
+    /// may be omitted and `default_step` is used instead.
+    fn parse(input: &str, default_step: Option<u64>) -> nom::IResult<&str, Self> {
+        let step = |input| match default_step {
+            Some(default) => combinator::map(combinator::opt(helpers::uint), |step| {
+                step.unwrap_or(default)
+            })(input),
+            None => helpers::uint(input),
+        };
+
         combinator::map(
             sequence::pair(
-                sequence::terminated(helpers::uint, helpers::newline),
+                sequence::terminated(step, helpers::newline),
                 combinator::opt(Model::parse),
             ),
             |(step, model)| Transition {
@@ -157,16 +273,19 @@ impl Transition {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WitnessFrame {
     pub state_part: Option<Transition>,
     pub input_part: Transition,
 }
 
 impl WitnessFrame {
-    fn parse(input: &str) -> nom::IResult<&str, Self> {
-        let part_with_prefix =
-            |prefix| sequence::preceded(complete::tag(prefix), Transition::parse);
+    fn parse(input: &str, default_step: Option<u64>) -> nom::IResult<&str, Self> {
+        let part_with_prefix = |prefix| {
+            sequence::preceded(complete::tag(prefix), move |input| {
+                Transition::parse(input, default_step)
+            })
+        };
 
         let state_part = part_with_prefix("#");
         let input_part = part_with_prefix("@");
@@ -180,23 +299,42 @@ impl WitnessFrame {
         )(input)
     }
 
-    fn parse_multi(input: &str) -> nom::IResult<&str, Vec<Self>> {
-        multi::many1(Self::parse)(input)
+    /// Parses all frames of the witness. There might be no frames, e.g. if the property is
+    /// violated already in the initial state. If `lenient` is set, frames may omit their steps,
+    /// which are then taken to be the position of the frame.
+    fn parse_multi(input: &str, lenient: bool) -> nom::IResult<&str, Vec<Self>> {
+        let mut frames = Vec::new();
+        let mut rest = input;
+
+        loop {
+            let default_step = lenient.then_some(frames.len() as u64);
+
+            match Self::parse(rest, default_step) {
+                Ok((next, frame)) => {
+                    frames.push(frame);
+                    rest = next;
+                }
+                Err(nom::Err::Error(_)) => return Ok((rest, frames)),
+                Err(err) => return Err(err),
+            }
+        }
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct WitnessFormat {
     pub header: WitnessHeader,
     pub frames: Vec<WitnessFrame>,
 }
 
 impl WitnessFormat {
-    pub fn parse(input: &str) -> nom::IResult<&str, Self> {
+    /// Parses a single witness format, recognizing properties in the header by the tags of the
+    /// options.
+    pub fn parse<'a>(options: &ParseOptions, input: &'a str) -> nom::IResult<&'a str, Self> {
         combinator::map(
             sequence::tuple((
-                WitnessHeader::parse,
-                WitnessFrame::parse_multi,
+                |input| WitnessHeader::parse(&options.prop_tags, input),
+                |input| WitnessFrame::parse_multi(input, options.lenient),
                 complete::tag("."),
                 combinator::opt(helpers::newline),
             )),
@@ -207,3 +345,86 @@ impl WitnessFormat {
         )(input)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_format(input: &str) -> WitnessFormat {
+        let (rest, format) =
+            WitnessFormat::parse(&ParseOptions::default(), input).expect("witness is valid");
+        assert_eq!(rest, "");
+
+        format
+    }
+
+    #[test]
+    fn custom_prop_tag_is_parsed() {
+        let options = ParseOptions {
+            prop_tags: "b=bad,j=justice,a=assert".parse().expect("tags are valid"),
+            ..Default::default()
+        };
+
+        let (_, format) = WitnessFormat::parse(&options, "sat\na0b1\n.\n").expect("valid");
+        let kinds: Vec<_> = format
+            .header
+            .props
+            .iter()
+            .map(|prop| (prop.kind, prop.idx))
+            .collect();
+
+        assert_eq!(kinds, [(PropKind::Bad, 0), (PropKind::Bad, 1)]);
+    }
+
+    #[test]
+    fn unknown_prop_tag_kind_is_rejected() {
+        let err = "a=unknown".parse::<PropTags>().unwrap_err();
+        assert_eq!(err, "Unknown prop kind: 'unknown'");
+    }
+
+    const STEPLESS: &str = "sat\nb0\n#\n0 0 x#0\n@\n0 1 in@0\n@\n0 0 in@1\n.\n";
+
+    #[test]
+    fn stepless_transition_takes_default_step() {
+        let (rest, transition) = Transition::parse("\n0 1 in@0\n", Some(3)).expect("valid");
+
+        assert_eq!(rest, "");
+        assert_eq!(transition.step, 3);
+        assert_eq!(transition.model.assignments.len(), 1);
+    }
+
+    #[test]
+    fn lenient_frames_are_numbered_by_position() {
+        let options = ParseOptions {
+            lenient: true,
+            ..Default::default()
+        };
+
+        let (_, format) = WitnessFormat::parse(&options, STEPLESS).expect("valid");
+        let steps: Vec<_> = format
+            .frames
+            .iter()
+            .map(|frame| frame.input_part.step)
+            .collect();
+
+        assert_eq!(steps, [0, 1]);
+        assert_eq!(
+            format.frames[0].state_part.as_ref().map(|sp| sp.step),
+            Some(0)
+        );
+    }
+
+    #[test]
+    fn stepless_transition_is_rejected_without_lenient() {
+        assert!(Transition::parse("\n0 1 in@0\n", None).is_err());
+        assert!(WitnessFormat::parse(&ParseOptions::default(), STEPLESS).is_err());
+    }
+
+    #[test]
+    fn witness_without_frames_is_accepted() {
+        let format = parse_format("sat\nb0\n.\n");
+
+        assert_eq!(format.header.props.len(), 1);
+        assert!(format.frames.is_empty());
+    }
+}
@@ -0,0 +1,156 @@
+use std::{fmt::Display, path::PathBuf};
+
+/// Errors returned by the parsing and analysis functions of periscope.
+#[derive(Debug)]
+pub enum PeriscopeError {
+    /// The witness could not be parsed.
+    ParseFailed(ParseError),
+
+    /// `btormc` reported `unsat`, so there is no witness to analyze.
+    UnsatInput,
+
+    /// Witness does not match the model or other witnesses, e.g. signals have different widths.
+    ModelMismatch(String),
+
+    /// Input is well-formed, but can't be used as requested, e.g. an unknown signal was selected.
+    InvalidInput(String),
+
+    /// `btormc` could not be found in `PATH`.
+    BtormcNotFound,
+
+    /// External tool, such as a decompressor, could not be started.
+    Tool {
+        tool: String,
+        source: std::io::Error,
+    },
+
+    /// Listening for a witness on the address failed.
+    Listen {
+        addr: String,
+        source: std::io::Error,
+    },
+
+    /// File or directory could not be opened or created.
+    File {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+
+    /// Reading the input or writing the report failed.
+    Io(std::io::Error),
+}
+
+pub type Result<T> = std::result::Result<T, PeriscopeError>;
+
+/// Where and why parsing a witness failed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    /// Kind of the parser that failed, `None` if the input was rejected as a whole, e.g. because
+    /// it is empty.
+    pub kind: Option<nom::error::ErrorKind>,
+
+    /// Line of the input at which parsing failed, starting at 1.
+    pub line: usize,
+
+    /// Rest of the line at which parsing failed.
+    pub input: String,
+
+    /// Description of the failure.
+    pub message: String,
+}
+
+impl ParseError {
+    /// Error at the start of `rest`, which is the remaining part of `input`.
+    pub(crate) fn at(input: &str, rest: &str, kind: Option<nom::error::ErrorKind>) -> Self {
+        let consumed = &input[..input.len() - rest.len()];
+
+        Self {
+            kind,
+            line: consumed.matches('\n').count() + 1,
+            input: rest.lines().next().unwrap_or_default().to_string(),
+            message: match kind {
+                Some(kind) => format!("Expected {}", kind.description().to_lowercase()),
+                None => String::from("Unexpected input"),
+            },
+        }
+    }
+
+    /// Error about the input as a whole.
+    pub(crate) fn input(message: &str) -> Self {
+        Self {
+            kind: None,
+            line: 1,
+            input: String::new(),
+            message: message.to_string(),
+        }
+    }
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.input.is_empty() && self.kind.is_none() {
+            return write!(f, "{}", self.message);
+        }
+
+        write!(
+            f,
+            "{} at line {}: '{}'",
+            self.message, self.line, self.input
+        )
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+impl PeriscopeError {
+    pub(crate) fn file(path: impl Into<PathBuf>) -> impl FnOnce(std::io::Error) -> Self {
+        let path = path.into();
+        move |source| PeriscopeError::File { path, source }
+    }
+}
+
+impl Display for PeriscopeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PeriscopeError::ParseFailed(cause) => {
+                write!(f, "Failed to parse witness. Cause: {cause}")
+            }
+            PeriscopeError::UnsatInput => {
+                write!(f, "No counterexample found, btormc reported 'unsat'.")
+            }
+            PeriscopeError::ModelMismatch(msg) | PeriscopeError::InvalidInput(msg) => {
+                write!(f, "{msg}")
+            }
+            PeriscopeError::BtormcNotFound => {
+                write!(
+                    f,
+                    "Could not find 'btormc', make sure it is installed and in PATH."
+                )
+            }
+            PeriscopeError::Tool { tool, .. } => write!(f, "Could not run '{tool}'."),
+            PeriscopeError::Listen { addr, .. } => write!(f, "Could not listen on '{addr}'."),
+            PeriscopeError::File { path, .. } => {
+                write!(f, "Could not open '{}'.", path.display())
+            }
+            PeriscopeError::Io(_) => write!(f, "I/O error."),
+        }
+    }
+}
+
+impl std::error::Error for PeriscopeError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            PeriscopeError::Tool { source, .. }
+            | PeriscopeError::Listen { source, .. }
+            | PeriscopeError::File { source, .. }
+            | PeriscopeError::Io(source) => Some(source),
+            _ => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for PeriscopeError {
+    fn from(err: std::io::Error) -> Self {
+        PeriscopeError::Io(err)
+    }
+}
@@ -1,9 +1,17 @@
 use std::path::PathBuf;
 
-use clap::{Parser, Subcommand};
+use bench::{FailOn, SortBy, TimeUnit};
+use btor::{ListFormat, ParseOptions, PropTags, ReportOptions};
+use clap::{ArgGroup, Parser, Subcommand};
+use schema::SchemaKind;
 
 pub mod bench;
+pub mod browser;
 pub mod btor;
+pub mod error;
+pub mod output;
+pub mod pager;
+pub mod schema;
 
 #[derive(Debug, Clone, Parser)]
 #[clap(long_about)]
@@ -12,18 +20,201 @@ pub struct Config {
     /// file is not provided.
     #[command(subcommand)]
     pub command: Commands,
+
+    /// Only print reports, results and errors. Informational messages, progress and warnings are
+    /// suppressed.
+    #[arg(short, long, global = true)]
+    pub quiet: bool,
 }
 
 #[derive(Debug, Clone, Subcommand)]
 #[command(long_about)]
 pub enum Commands {
+    #[command(group(ArgGroup::new("model").args(["btor2", "stdin_model"])))]
     ParseWitness {
-        /// Path to the witness file.
+        /// Path to the witness file. Named pipes are read like regular files.
         file: Option<PathBuf>,
 
+        /// Read the witness from the first TCP connection to the given address, e.g.
+        /// '127.0.0.1:4000', instead of a file or stdin.
+        #[arg(long, value_name = "ADDR", conflicts_with = "file")]
+        listen: Option<String>,
+
+        /// Path to the BTOR2 model file, typically ends with '.btor2' extension.
+        #[arg(short, long)]
+        btor2: Option<PathBuf>,
+
+        /// Read both the witness and the BTOR2 model from stdin. The witness comes first and is
+        /// separated from the model by a line containing only the given marker. The marker
+        /// defaults to '===BTOR2===', a custom one is given as '--stdin-model=MARKER'.
+        #[arg(
+            long,
+            value_name = "MARKER",
+            num_args = 0..=1,
+            require_equals = true,
+            default_missing_value = "===BTOR2===",
+            conflicts_with_all = ["file", "listen", "btor2"]
+        )]
+        stdin_model: Option<String>,
+
+        /// Report every '.witness' file in the directory instead of a single witness. Each
+        /// report is written into the 'outdir' as '<file stem>.<extension of the format>'.
+        /// Witnesses that fail are skipped and counted in the summary.
+        #[arg(
+            long,
+            value_name = "DIR",
+            requires = "outdir",
+            conflicts_with_all = ["file", "listen", "stdin_model", "pager", "tui"]
+        )]
+        batch: Option<PathBuf>,
+
+        /// Directory for the reports of 'batch', created if it doesn't exist.
+        #[arg(long, value_name = "DIR", requires = "batch")]
+        outdir: Option<PathBuf>,
+
+        /// Treat the input as several independent witnesses separated by lines containing only
+        /// the given marker. Each witness is analyzed and reported separately. The marker
+        /// defaults to '---', a custom one is given as '--split-on=MARKER'.
+        #[arg(
+            long,
+            value_name = "MARKER",
+            num_args = 0..=1,
+            require_equals = true,
+            default_missing_value = "---"
+        )]
+        split_on: Option<String>,
+
+        /// Show the report in a pager. The pager is taken from the 'PAGER' environment variable
+        /// and defaults to 'less -R'. The report is printed directly if the pager can't be
+        /// started.
+        #[arg(long)]
+        pager: bool,
+
+        /// Measure how long parsing the witness and collecting its flows takes instead of printing
+        /// the report. With '--format json', the size of the input and both durations in seconds
+        /// are written as a JSON object.
+        #[arg(long, conflicts_with_all = ["split_on", "batch", "pager", "tui"])]
+        bench: bool,
+
+        /// Browse the signal flows interactively instead of printing the report. Signals can be
+        /// listed, filtered by name and selected to show their flow, see the 'h' command for
+        /// help. Commands are read from stdin, so the witness file must be provided.
+        #[arg(long, requires = "file", conflicts_with_all = ["pager", "split_on"])]
+        tui: bool,
+
+        #[command(flatten)]
+        parse: ParseOptions,
+
+        #[command(flatten)]
+        report: Box<ReportOptions>,
+    },
+
+    /// List the bad and justice properties of a BTOR2 model together with their index in the
+    /// witness header.
+    Properties {
+        /// Path to the BTOR2 model file.
+        btor2: PathBuf,
+
+        /// Format of the list. The JSON format is an array of objects with 'index', 'node',
+        /// 'kind' and 'name' of each property.
+        #[arg(long, value_enum, default_value_t = ListFormat::Text)]
+        format: ListFormat,
+
+        /// Also print the line of the BTOR2 model that declares each property.
+        #[arg(long)]
+        show_source: bool,
+    },
+
+    /// Check that the node ids of a BTOR2 model are numeric and unique and that it only declares
+    /// properties periscope can report. These are the same checks that 'parse-witness --strict'
+    /// does on the model.
+    CheckBtor2 {
+        /// Path to the BTOR2 model file.
+        btor2: PathBuf,
+
+        /// Also print how many bad, justice, constraint, state and input nodes are declared in
+        /// the model.
+        #[arg(long)]
+        count_properties: bool,
+    },
+
+    /// Explain a single line of a witness, e.g. '5 00000110 x@3', by printing what the parsers of
+    /// periscope make of it.
+    Explain {
+        /// Line of the witness, such as an assignment, a frame header like '#3' or the properties
+        /// of the witness header like 'b0'.
+        line: String,
+
+        /// Letters that mark properties in the witness header and the kinds they stand for, e.g.
+        /// 'b=bad,j=justice,c=constraint'.
+        #[arg(long, value_name = "TAGS", default_value = PropTags::DEFAULT)]
+        prop_tags: PropTags,
+    },
+
+    /// Print the JSON Schema of the JSON output of periscope, so that other tools can validate
+    /// it.
+    Schema {
+        /// Which JSON output the schema describes.
+        #[arg(value_enum)]
+        what: SchemaKind,
+    },
+
+    /// Merge several witnesses of the same model, e.g. produced by running btormc for each
+    /// property separately.
+    Merge {
+        /// Paths to the witness files.
+        #[arg(required = true)]
+        files: Vec<PathBuf>,
+
         /// Path to the BTOR2 model file, typically ends with '.btor2' extension.
         #[arg(short, long)]
         btor2: Option<PathBuf>,
+
+        /// Write the merged witness in JSON format to this file instead of printing the report.
+        #[arg(short, long)]
+        out: Option<PathBuf>,
+
+        #[command(flatten)]
+        parse: ParseOptions,
+    },
+
+    /// Compare two witnesses step by step, e.g. counterexamples of a model before and after a
+    /// change. Reports the first step at which each signal differs and signals that are only
+    /// present in one of the witnesses.
+    DiffWitness {
+        /// Path to the first witness file.
+        a: PathBuf,
+
+        /// Path to the second witness file.
+        b: PathBuf,
+
+        /// Path to the BTOR2 model file, typically ends with '.btor2' extension.
+        #[arg(short, long)]
+        btor2: Option<PathBuf>,
+
+        #[command(flatten)]
+        parse: ParseOptions,
+    },
+
+    /// Compare the mean run times of benchmark results with those of a baseline, either another
+    /// results file or the committed version of the results file at a git revision.
+    Compare {
+        /// Path to the current results file.
+        current: PathBuf,
+
+        /// Path to the results file to compare against.
+        #[arg(long, required_unless_present = "since", conflicts_with = "since")]
+        baseline: Option<PathBuf>,
+
+        /// Compare against the current results file as it was committed at this git revision,
+        /// e.g. 'HEAD~1' or a tag. It is retrieved with 'git show' from the repository that
+        /// contains the file.
+        #[arg(long, value_name = "REV")]
+        since: Option<String>,
+
+        /// Unit of the printed run times.
+        #[arg(long, value_enum, default_value_t = TimeUnit::Auto)]
+        time_unit: TimeUnit,
     },
 
     Bench {
@@ -40,7 +231,7 @@ pub enum Commands {
         run_rotor: bool,
 
         /// Files that should be benchmarked. Files that do not match the provided names will be
-        /// ignored.
+        /// ignored. All files generated by rotor are benchmarked if no files are given.
         ///
         /// The 'filter-files' option has priority if both 'filter-files' and 'filter-config' are
         /// provided.
@@ -66,6 +257,11 @@ pub enum Commands {
         /// runs:
         ///   8-bit-codeword-size: "0 -codewordsize 8"
         ///   16-bit-codeword-size: "0 -codewordsize 16"
+        ///
+        /// # optional, replaces the default 'clean' and 'make-target' targets
+        /// make-targets:
+        ///   - "clean"
+        ///   - "rotor-symbolic"
         /// ```
         #[arg(short = 'c', long, requires = "run_rotor", verbatim_doc_comment)]
         bench_config: Option<PathBuf>,
@@ -75,14 +271,118 @@ pub enum Commands {
         #[arg(short = 's', long = "selfie-dir", required_if_eq("run_rotor", "true"))]
         selfie_dir: Option<PathBuf>,
 
-        /// Path to folder containing BTOR2 files. All BTOR2 files should have the ".btor2"
-        /// extension. Alternatively, path to a single BTOR2 file can be provided for single
-        /// benchmark.
+        /// Paths to BTOR2 files or folders containing BTOR2 files, which are benchmarked in the
+        /// given order. All BTOR2 files in a folder should have the ".btor2" extension.
         #[arg(required_unless_present("run_rotor"))]
-        path: Option<PathBuf>,
+        paths: Vec<PathBuf>,
+
+        /// Also collect BTOR2 files from subdirectories of the benchmarked directory.
+        #[arg(long)]
+        recursive: bool,
+
+        /// Only benchmark BTOR2 files whose name matches the glob, e.g. '*riscu*'. Can be repeated.
+        /// Supports '*', '?' and '[...]'. Files given directly as paths are always benchmarked.
+        /// With 'run-rotor', the globs further narrow the files selected by 'filter-files'.
+        #[arg(long, value_name = "GLOB")]
+        include_glob: Vec<String>,
+
+        /// Do not benchmark BTOR2 files whose name matches the glob, e.g. '*debug*'. Can be
+        /// repeated and takes precedence over 'include-glob'.
+        #[arg(long, value_name = "GLOB")]
+        exclude_glob: Vec<String>,
+
+        /// Order of the results in the results file. Files are ordered by name by default.
+        #[arg(long, value_enum, default_value_t = SortBy::Name)]
+        sort_by: SortBy,
+
+        /// Stop after the first failed benchmark. Results of the completed benchmarks are still
+        /// written into the results file.
+        #[arg(long)]
+        fail_fast: bool,
+
+        /// Which benchmarks count as failed for 'fail-fast'.
+        #[arg(long, value_enum, default_value_t = FailOn::Any, requires = "fail_fast")]
+        fail_on: FailOn,
 
         /// Target for runing `make` inside of the selfie directory.
         #[arg(short = 'm', long = "make-target", required_if_eq("run_rotor", "true"))]
         make_target: Option<String>,
+
+        /// Record the commit and modified files of the selfie git tree in the metadata of the
+        /// results. Prints a warning if the tree has modified or untracked files.
+        #[arg(long, requires = "run_rotor")]
+        verify_tree: bool,
+
+        /// How many times each file is benchmarked, 5 by default. If provided, the size of the
+        /// btormc dump is sampled as often and a warning is printed if it differs between
+        /// samples.
+        #[arg(long, value_parser = clap::value_parser!(u32).range(1..))]
+        samples: Option<u32>,
+
+        /// How many times each file is run before the measured runs, 3 by default. The timings of
+        /// these runs are discarded. If provided, the size of the btormc dump is additionally
+        /// sampled as often to check that the model is deterministic.
+        #[arg(long)]
+        warmup: Option<u32>,
+
+        /// Number of threads each btormc run may use, set through the 'OMP_NUM_THREADS'
+        /// environment variable. Overrides 'btormc-threads' of the config file. A warning is
+        /// printed if this is more than the number of cores.
+        #[arg(long, value_parser = clap::value_parser!(u32).range(1..))]
+        btormc_threads: Option<u32>,
+
+        /// Unit of the run times printed while benchmarking. The results file always stores
+        /// seconds.
+        #[arg(long, value_enum, default_value_t = TimeUnit::Auto)]
+        time_unit: TimeUnit,
+
+        /// Also write each event of the benchmarks, e.g. a started, finished, failed or timed out
+        /// benchmark, as a JSON object with a timestamp into this file, one object per line. The
+        /// file is written while benchmarking and replaced if it exists.
+        #[arg(long, value_name = "PATH")]
+        log_ndjson: Option<PathBuf>,
+
+        /// Arguments for a single rotor run named 'default'. This is an alternative to the
+        /// 'runs' in the config file, which must not be provided together with this option.
+        #[arg(long, requires = "run_rotor")]
+        rotor_args: Option<String>,
     },
 }
+
+#[cfg(test)]
+mod tests {
+    use clap::CommandFactory;
+
+    use super::*;
+
+    #[test]
+    fn command_is_valid() {
+        Config::command().debug_assert();
+    }
+
+    #[test]
+    fn report_modes_are_exclusive() {
+        for modes in [
+            ["--count", "--table"],
+            ["--oscillating", "--first-change"],
+            ["--merge-flows", "--events"],
+            ["--init-only", "--signal-stats"],
+        ] {
+            let args = ["periscope", "parse-witness", "a.wit", modes[0], modes[1]];
+            assert!(
+                Config::try_parse_from(args).is_err(),
+                "{modes:?} are accepted"
+            );
+        }
+
+        let args = [
+            "periscope",
+            "parse-witness",
+            "a.wit",
+            "--table",
+            "--context",
+            "1",
+        ];
+        assert!(Config::try_parse_from(args).is_ok());
+    }
+}
@@ -1,58 +1,628 @@
-use std::{ffi::OsStr, io::Read, path::PathBuf};
+use std::{
+    ffi::OsStr,
+    fs::File,
+    io::{Read, Write},
+    path::{Path, PathBuf},
+    time::Instant,
+};
 
 use anyhow::Context;
 use clap::Parser;
 use periscope::{
-    bench::{self, BenchConfig},
-    btor::{self},
-    Commands, Config,
+    bench::{self, BenchConfig, TimeUnit},
+    browser,
+    btor::{self, ListFormat, OutputFormat, ParseOptions, ReportOptions, Witness, WitnessOutcome},
+    output,
+    pager::Pager,
+    schema, Commands, Config,
 };
 
 fn main() -> anyhow::Result<()> {
     let config = Config::parse();
+    output::set_quiet(config.quiet);
 
     match config.command {
-        Commands::ParseWitness { file, btor2 } => {
-            let witness: Box<dyn Read> = match file {
-                Some(path) => Box::new(std::fs::File::open(path).unwrap()),
-                None => Box::new(std::io::stdin()),
+        Commands::ParseWitness {
+            file,
+            listen,
+            btor2,
+            stdin_model,
+            batch,
+            outdir,
+            split_on,
+            pager,
+            bench,
+            tui,
+            parse,
+            report,
+        } => {
+            if let (Some(batch), Some(outdir)) = (batch, outdir) {
+                let model = read_model(btor2)?;
+                return write_batch_reports(&batch, &outdir, model, split_on, &parse, &report);
+            }
+
+            if let Some(marker) = stdin_model {
+                let mut input = String::new();
+                std::io::stdin()
+                    .read_to_string(&mut input)
+                    .context("Failed reading stdin.")?;
+
+                let (witness, model) = btor::split_model(&input, &marker).with_context(|| {
+                    format!(
+                        "No line '{marker}' separates the witness from the BTOR2 model on stdin."
+                    )
+                })?;
+                let witness = Box::new(std::io::Cursor::new(witness.to_string()));
+
+                if bench {
+                    return bench_witness(witness, Some(model.to_string()), &parse, &report);
+                }
+
+                return parse_witness(
+                    witness,
+                    Some(model.to_string()),
+                    split_on,
+                    pager,
+                    tui,
+                    parse,
+                    *report,
+                );
+            }
+
+            let witness: Box<dyn Read> = match (file, listen) {
+                (Some(path), _) => btor::open_maybe_compressed(path)?,
+                (None, Some(addr)) => btor::accept_witness(&addr)?,
+                (None, None) => Box::new(std::io::stdin()),
             };
 
-            let btor2 = btor2.and_then(|path| {
-                std::fs::File::open(path)
-                    .inspect_err(|err| {
-                        format!("Could not open provided btor2 file: {}", err);
-                    })
-                    .ok()
-            });
+            let model = read_model(btor2)?;
+
+            if bench {
+                return bench_witness(witness, model, &parse, &report);
+            }
 
-            let witness = btor::parse_btor_witness(witness, btor2)?;
+            parse_witness(witness, model, split_on, pager, tui, parse, *report)?
+        }
+        Commands::Merge {
+            files,
+            btor2,
+            out,
+            parse,
+        } => merge_witnesses(files, btor2, out, parse)?,
+        Commands::DiffWitness { a, b, btor2, parse } => {
+            let read = |path: &PathBuf| -> anyhow::Result<Witness> {
+                let witness = btor::analyze_witness_file(path, btor2.as_ref(), &parse)?
+                    .into_witness()
+                    .with_context(|| format!("Can't compare '{}'.", path.display()))?;
+
+                Ok(witness)
+            };
+
+            let diff = read(&a)?.diff(&read(&b)?)?;
+            diff.write_report(&mut std::io::stdout().lock())?;
+        }
+        Commands::Properties {
+            btor2,
+            format,
+            show_source,
+        } => list_properties(btor2, format, show_source)?,
+        Commands::CheckBtor2 {
+            btor2,
+            count_properties,
+        } => check_btor2(btor2, count_properties)?,
+        Commands::Explain { line, prop_tags } => {
+            println!("{}", btor::explain_line(&line, &prop_tags)?);
+        }
+        Commands::Compare {
+            current,
+            baseline,
+            since,
+            time_unit,
+        } => {
+            let baseline = match (baseline, since) {
+                (Some(path), _) => bench::Baseline::File(path),
+                (None, Some(rev)) => bench::Baseline::Revision(rev),
+                (None, None) => anyhow::bail!("A baseline or a revision is required."),
+            };
 
-            witness.analyze_and_report();
+            bench::compare_results(&baseline, &current, time_unit)?;
+        }
+        Commands::Schema { what } => {
+            serde_json::to_writer_pretty(std::io::stdout().lock(), &schema::schema(what))
+                .context("Failed writing the schema.")?;
+            println!();
         }
         Commands::Bench {
-            path,
+            paths,
             run_rotor,
             results_path,
             filter_files,
             bench_config,
             selfie_dir,
+            recursive,
+            include_glob,
+            exclude_glob,
+            sort_by,
+            fail_fast,
+            fail_on,
             make_target,
+            rotor_args,
+            verify_tree,
+            samples,
+            warmup,
+            btormc_threads,
+            time_unit,
+            log_ndjson,
         } => {
-            let path = if run_rotor {
-                selfie_dir.context("Selfie directory is required when running rotor.")?
+            let paths = if run_rotor {
+                vec![selfie_dir.context("Selfie directory is required when running rotor.")?]
+            } else if paths.is_empty() {
+                anyhow::bail!(
+                    "Path to a BTOR2 file or directory containing BTOR2 files is required."
+                );
             } else {
-                path.context(
-                    "Path to a BTOR2 file or directory containing BTOR2 files is required.",
-                )?
+                paths
             };
 
-            let config = prepare_bench_config(run_rotor, filter_files, bench_config, results_path)?;
+            let mut config = prepare_bench_config(
+                run_rotor,
+                filter_files,
+                bench_config,
+                results_path,
+                rotor_args,
+            )?;
+            config.recursive = recursive;
+            config.include_globs = include_glob;
+            config.exclude_globs = exclude_glob;
+            config.sort_by = sort_by;
+            config.fail_fast = fail_fast;
+            config.fail_on = fail_on;
+            config.verify_tree = verify_tree;
+            config.samples = samples;
+            config.warmup = warmup;
+
+            if btormc_threads.is_some() {
+                config.btormc_threads = btormc_threads;
+            }
+
+            let mut log = log_ndjson
+                .as_deref()
+                .map(bench::EventLog::create)
+                .transpose()?;
+
+            bench::run_benches(paths, config, make_target, &mut |event| {
+                if let Some(log) = &mut log {
+                    if let Err(err) = log.write(&event) {
+                        output::warn(format!("{err:#}"));
+                    }
+                }
+
+                bench::print_event(event, time_unit)
+            })?;
+        }
+    };
+
+    Ok(())
+}
+
+fn parse_witness(
+    witness: Box<dyn Read>,
+    model: Option<String>,
+    split_on: Option<String>,
+    pager: bool,
+    tui: bool,
+    parse: ParseOptions,
+    report: ReportOptions,
+) -> anyhow::Result<()> {
+    if let (true, Some(model)) = (report.strict, &model) {
+        check_model(model)?;
+    }
+
+    if tui {
+        let mut out = std::io::stdout();
+
+        return match read_witness(witness, model.as_deref(), &parse, &report, &mut out)? {
+            Some(witness) => {
+                browser::browse(&witness, &report, std::io::stdin().lock(), &mut out)?;
+                Ok(())
+            }
+            None => Ok(()),
+        };
+    }
+
+    let mut pager = pager.then(Pager::spawn);
+    let mut stdout = std::io::stdout();
+    let out: &mut dyn Write = match &mut pager {
+        Some(pager) => pager,
+        None => &mut stdout,
+    };
+
+    // the report is shown even if an assertion about the witness failed
+    let result = write_reports(witness, model.as_deref(), split_on, &parse, &report, out);
+
+    if let Some(pager) = pager {
+        pager.finish().context("Failed running the pager.")?;
+    }
+
+    result
+}
+
+/// Prints the size of the witness and how long parsing it and collecting its flows took.
+fn bench_witness(
+    mut witness: Box<dyn Read>,
+    model: Option<String>,
+    parse: &ParseOptions,
+    report: &ReportOptions,
+) -> anyhow::Result<()> {
+    let mut input = String::new();
+    witness
+        .read_to_string(&mut input)
+        .context("Failed reading witness.")?;
+
+    let start = Instant::now();
+    let outcome = btor::parse_btor_witness(
+        input.as_bytes(),
+        model.as_ref().map(String::as_bytes),
+        parse,
+    )?;
+    let parse_time = start.elapsed().as_secs_f64();
+
+    let analyze_time = match &outcome {
+        WitnessOutcome::Sat(witness) => {
+            let start = Instant::now();
+
+            if !report.no_input {
+                witness.analyze_input_flow(report)?;
+            }
+            if !report.no_state {
+                witness.analyze_state_flow(report)?;
+            }
+
+            Some(start.elapsed().as_secs_f64())
+        }
+        WitnessOutcome::Unsat => None,
+    };
+
+    let mut out = std::io::stdout().lock();
+
+    if report.format == OutputFormat::Json {
+        let times = serde_json::json!({
+            "input_bytes": input.len(),
+            "parse_seconds": parse_time,
+            "analyze_seconds": analyze_time,
+        });
+        serde_json::to_writer_pretty(&mut out, &times).context("Failed writing the times.")?;
+        writeln!(out)?;
+
+        return Ok(());
+    }
+
+    let format_time = |seconds| bench::format_time(seconds, TimeUnit::Auto);
+
+    writeln!(out, "Input: {} bytes", input.len())?;
+    writeln!(out, "Parse: {}", format_time(parse_time))?;
+
+    match analyze_time {
+        Some(analyze_time) => writeln!(out, "Analyze: {}", format_time(analyze_time))?,
+        None => writeln!(out, "Analyze: skipped, btormc reported 'unsat'")?,
+    }
+
+    Ok(())
+}
+
+fn read_model(btor2: Option<PathBuf>) -> anyhow::Result<Option<String>> {
+    btor2
+        .map(|path| {
+            std::fs::read_to_string(&path)
+                .with_context(|| format!("Could not read BTOR2 file '{}'.", path.display()))
+        })
+        .transpose()
+}
+
+/// Writes the report of each '.witness' file in `batch` into `outdir`. Witnesses that can't be
+/// reported are skipped with a warning, the batch fails at the end if any witness was skipped.
+fn write_batch_reports(
+    batch: &Path,
+    outdir: &Path,
+    model: Option<String>,
+    split_on: Option<String>,
+    parse: &ParseOptions,
+    report: &ReportOptions,
+) -> anyhow::Result<()> {
+    if let (true, Some(model)) = (report.strict, &model) {
+        check_model(model)?;
+    }
+
+    let mut files: Vec<PathBuf> = std::fs::read_dir(batch)
+        .with_context(|| format!("Could not read directory '{}'.", batch.display()))?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.is_file() && path.extension() == Some(OsStr::new("witness")))
+        .collect();
+    files.sort();
+
+    std::fs::create_dir_all(outdir)
+        .with_context(|| format!("Failed creating '{}'.", outdir.display()))?;
+
+    let mut failed = 0;
+
+    for file in &files {
+        let stem = file.file_stem().unwrap_or_default();
+        let out_path = outdir.join(stem).with_extension(report.format.extension());
+
+        let result = File::create(&out_path)
+            .with_context(|| format!("Failed creating '{}'.", out_path.display()))
+            .and_then(|mut out| {
+                let witness = btor::open_maybe_compressed(file)?;
+                write_reports(
+                    witness,
+                    model.as_deref(),
+                    split_on.clone(),
+                    parse,
+                    report,
+                    &mut out,
+                )
+            });
+
+        if let Err(err) = result {
+            failed += 1;
+            output::warn(format!("skipping '{}': {err:#}", file.display()));
+            // partially written reports are misleading
+            let _ = std::fs::remove_file(&out_path);
+        }
+    }
+
+    println!(
+        "Reported {} of {} witnesses into '{}'.",
+        files.len() - failed,
+        files.len(),
+        outdir.display()
+    );
+
+    if failed > 0 {
+        anyhow::bail!("{failed} witnesses could not be reported.");
+    }
+
+    Ok(())
+}
+
+/// Prints the report about the witness, or about each witness if the input is split into several
+/// witnesses by the `split_on` marker.
+fn write_reports(
+    mut witness: impl Read,
+    model: Option<&str>,
+    split_on: Option<String>,
+    parse: &ParseOptions,
+    report: &ReportOptions,
+    out: &mut dyn Write,
+) -> anyhow::Result<()> {
+    let Some(marker) = split_on else {
+        return report_witness(witness, model, parse, report, out);
+    };
+
+    let mut input = String::new();
+    witness
+        .read_to_string(&mut input)
+        .context("Failed reading witness.")?;
+
+    let segments = btor::split_witnesses(&input, &marker);
+
+    for (idx, segment) in segments.iter().enumerate() {
+        if idx > 0 {
+            writeln!(out)?;
+        }
+
+        writeln!(out, "Witness {} of {}:", idx + 1, segments.len())?;
+        report_witness(segment.as_bytes(), model, parse, report, out)
+            .with_context(|| format!("Failed analyzing witness {}.", idx + 1))?;
+    }
+
+    Ok(())
+}
+
+/// Parses a single witness and prints the report about it.
+fn report_witness(
+    witness: impl Read,
+    model: Option<&str>,
+    parse: &ParseOptions,
+    report: &ReportOptions,
+    out: &mut dyn Write,
+) -> anyhow::Result<()> {
+    match read_witness(witness, model, parse, report, out)? {
+        Some(witness) => {
+            witness.analyze_and_report(report, out)?;
+            check_assertions(&witness, report)
+        }
+        None => Ok(()),
+    }
+}
+
+/// Fails if the witness contradicts 'assert-unsat' or 'assert-steps'. Witnesses without a
+/// counterexample satisfy both.
+fn check_assertions(witness: &Witness, report: &ReportOptions) -> anyhow::Result<()> {
+    let steps = witness
+        .props_in_steps()
+        .iter()
+        .map(|(_, steps)| *steps)
+        .max()
+        .unwrap_or_default();
+
+    if report.assert_unsat {
+        anyhow::bail!("Expected 'unsat', but the witness has a counterexample in {steps} steps.");
+    }
+
+    if let Some(max_steps) = report.assert_steps.filter(|max_steps| steps > *max_steps) {
+        anyhow::bail!("Counterexample has {steps} steps, more than the asserted {max_steps}.");
+    }
+
+    Ok(())
+}
+
+/// Parses a single witness. Returns `None` if no counterexample was found.
+fn read_witness(
+    witness: impl Read,
+    model: Option<&str>,
+    parse: &ParseOptions,
+    report: &ReportOptions,
+    out: &mut dyn Write,
+) -> anyhow::Result<Option<Witness>> {
+    let witness = match btor::parse_btor_witness(witness, model.map(str::as_bytes), parse)? {
+        WitnessOutcome::Sat(witness) => witness,
+        WitnessOutcome::Unsat => {
+            writeln!(out, "No counterexample found, btormc reported 'unsat'.")?;
+            return Ok(None);
+        }
+    };
+
+    if let (true, Some(model)) = (report.strict, model) {
+        let declared = btor::get_declared_signals(model.as_bytes());
+
+        for signal in witness.undeclared_signals(&declared) {
+            output::warn(format!(
+                "signal '{signal}' is not declared as a state or input in the BTOR2 model."
+            ));
+        }
+    }
+
+    Ok(Some(witness))
+}
+
+fn list_properties(btor2: PathBuf, format: ListFormat, show_source: bool) -> anyhow::Result<()> {
+    let properties = btor::parse_btor2_properties(&btor2)?;
+    let mut out = std::io::stdout().lock();
+
+    match format {
+        ListFormat::Text => {
+            for (idx, property) in &properties {
+                write!(out, "{:?} at {idx}", property._kind)?;
+
+                if let Some(name) = &property.name {
+                    write!(out, " named '{name}'")?;
+                }
+
+                writeln!(out, " with nid: {}", property.node)?;
+
+                if show_source {
+                    writeln!(out, "    {}", property.source)?;
+                }
+            }
+        }
+        ListFormat::Json => {
+            let entries: Vec<_> = properties
+                .iter()
+                .map(|(idx, property)| {
+                    let mut entry = serde_json::json!({
+                        "index": idx,
+                        "node": property.node,
+                        "kind": property._kind,
+                        "name": property.name,
+                    });
+
+                    if show_source {
+                        entry["source"] = serde_json::Value::from(property.source.as_str());
+                    }
+
+                    entry
+                })
+                .collect();
 
-            bench::run_benches(path, config, make_target)?;
+            serde_json::to_writer_pretty(&mut out, &entries)
+                .context("Failed writing the properties.")?;
+            writeln!(out)?;
         }
+    }
+
+    Ok(())
+}
+
+fn check_btor2(btor2: PathBuf, count_properties: bool) -> anyhow::Result<()> {
+    let model = std::fs::read_to_string(&btor2)
+        .with_context(|| format!("Could not read BTOR2 file '{}'.", btor2.display()))?;
+    let mut out = std::io::stdout().lock();
+
+    if count_properties {
+        let counts = btor::count_declarations(model.as_bytes());
+
+        writeln!(out, "Declarations:")?;
+        writeln!(out, "    bad:        {}", counts.bad)?;
+        writeln!(out, "    justice:    {}", counts.justice)?;
+        writeln!(out, "    constraint: {}", counts.constraint)?;
+        writeln!(out, "    state:      {}", counts.state)?;
+        writeln!(out, "    input:      {}", counts.input)?;
+    }
+
+    check_model(&model)?;
+
+    output::info(format!("No problems found in '{}'.", btor2.display()));
+
+    Ok(())
+}
+
+/// Fails with a list of the problems if the node ids of the BTOR2 model are invalid or if it
+/// declares properties that can't be reported.
+fn check_model(model: &str) -> anyhow::Result<()> {
+    let format_problems = |problems: Vec<btor::Btor2Problem>| {
+        problems
+            .iter()
+            .map(|problem| format!("    {problem}"))
+            .collect::<Vec<_>>()
+            .join("\n")
     };
 
+    let problems = btor::check_node_ids(model.as_bytes());
+    if !problems.is_empty() {
+        anyhow::bail!(
+            "Invalid node ids in BTOR2 model:\n{}",
+            format_problems(problems)
+        );
+    }
+
+    let problems = btor::check_property_kinds(model.as_bytes());
+    if !problems.is_empty() {
+        anyhow::bail!(
+            "Unsupported properties in BTOR2 model:\n{}",
+            format_problems(problems)
+        );
+    }
+
+    Ok(())
+}
+
+fn merge_witnesses(
+    files: Vec<PathBuf>,
+    btor2: Option<PathBuf>,
+    out: Option<PathBuf>,
+    parse: ParseOptions,
+) -> anyhow::Result<()> {
+    let mut witnesses = Vec::with_capacity(files.len());
+
+    for file in &files {
+        match btor::analyze_witness_file(file, btor2.as_ref(), &parse)? {
+            WitnessOutcome::Sat(witness) => witnesses.push(witness),
+            WitnessOutcome::Unsat => output::info(format!(
+                "Skipping '{}', it contains no counterexample.",
+                file.display()
+            )),
+        }
+    }
+
+    let (merged, conflicts) = Witness::merge(witnesses)?;
+
+    for conflict in &conflicts {
+        output::warn(format!("conflicting values, {conflict}."));
+    }
+
+    match out {
+        Some(out) => {
+            let file = File::create(&out)
+                .with_context(|| format!("Failed creating '{}'.", out.display()))?;
+            serde_json::to_writer_pretty(file, &merged)
+                .context("Failed serializing merged witness.")?;
+        }
+        None => {
+            merged.analyze_and_report(&ReportOptions::default(), &mut std::io::stdout().lock())?
+        }
+    }
+
     Ok(())
 }
 
@@ -61,6 +631,7 @@ fn prepare_bench_config(
     filter_files: Vec<String>,
     bench_config: Option<PathBuf>,
     results_path: Option<PathBuf>,
+    rotor_args: Option<String>,
 ) -> anyhow::Result<BenchConfig> {
     let mut config = BenchConfig::default();
 
@@ -86,8 +657,26 @@ fn prepare_bench_config(
         if !filter_files.is_empty() {
             config.files = filter_files;
         }
+
+        if let Some(rotor_args) = rotor_args {
+            if !config.runs.is_empty() {
+                anyhow::bail!(
+                    "Rotor arguments can be given either with 'rotor-args' or as 'runs' in the config, not both."
+                );
+            }
+
+            config.runs.insert(String::from("default"), rotor_args);
+        }
+
+        if config.runs.is_empty() {
+            anyhow::bail!(
+                "Running rotor requires at least one run, given with 'rotor-args' or as 'runs' in the config."
+            );
+        }
     }
 
+    config.validate()?;
+
     config.results_path = results_path;
 
     Ok(config)
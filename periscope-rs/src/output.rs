@@ -0,0 +1,30 @@
+use std::{
+    fmt::Display,
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+static QUIET: AtomicBool = AtomicBool::new(false);
+
+/// Suppresses informational messages and warnings for the rest of the program. Reports and
+/// errors are not affected.
+pub fn set_quiet(quiet: bool) {
+    QUIET.store(quiet, Ordering::Relaxed);
+}
+
+pub fn is_quiet() -> bool {
+    QUIET.load(Ordering::Relaxed)
+}
+
+/// Prints an informational message to stderr, unless running quietly.
+pub fn info(msg: impl Display) {
+    if !is_quiet() {
+        eprintln!("{msg}");
+    }
+}
+
+/// Prints a non-fatal warning to stderr, unless running quietly.
+pub fn warn(msg: impl Display) {
+    if !is_quiet() {
+        eprintln!("Warning: {msg}");
+    }
+}
@@ -0,0 +1,82 @@
+use std::{
+    io::{ErrorKind, Write},
+    process::{Child, ChildStdin, Command, Stdio},
+};
+
+use crate::output;
+
+/// Pager that is used if the `PAGER` environment variable is not set.
+const DEFAULT_PAGER: &str = "less -R";
+
+/// Output that is shown through a pager such as `less`. Falls back to stdout if the pager could
+/// not be started.
+pub enum Pager {
+    Spawned { child: Child, stdin: ChildStdin },
+    Stdout(std::io::Stdout),
+}
+
+impl Pager {
+    /// Starts the pager given by the `PAGER` environment variable, `less -R` by default.
+    pub fn spawn() -> Self {
+        let pager = std::env::var("PAGER")
+            .ok()
+            .filter(|pager| !pager.trim().is_empty())
+            .unwrap_or_else(|| DEFAULT_PAGER.to_string());
+
+        let mut args = pager.split_whitespace();
+        let program = args.next().unwrap_or("less");
+
+        let child = Command::new(program)
+            .args(args)
+            .stdin(Stdio::piped())
+            .spawn();
+
+        match child {
+            Ok(mut child) => match child.stdin.take() {
+                Some(stdin) => Pager::Spawned { child, stdin },
+                None => Pager::Stdout(std::io::stdout()),
+            },
+            Err(err) => {
+                output::warn(format!(
+                    "could not start pager '{pager}', writing to stdout: {err}"
+                ));
+                Pager::Stdout(std::io::stdout())
+            }
+        }
+    }
+
+    /// Closes the input of the pager and waits until the user quits it.
+    pub fn finish(self) -> std::io::Result<()> {
+        match self {
+            Pager::Spawned { mut child, stdin } => {
+                drop(stdin);
+                child.wait()?;
+                Ok(())
+            }
+            Pager::Stdout(mut stdout) => stdout.flush(),
+        }
+    }
+}
+
+impl Write for Pager {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        match self {
+            // the user quit the pager before reading everything, rest of the output is dropped
+            Pager::Spawned { stdin, .. } => match stdin.write(buf) {
+                Err(err) if err.kind() == ErrorKind::BrokenPipe => Ok(buf.len()),
+                res => res,
+            },
+            Pager::Stdout(stdout) => stdout.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        match self {
+            Pager::Spawned { stdin, .. } => match stdin.flush() {
+                Err(err) if err.kind() == ErrorKind::BrokenPipe => Ok(()),
+                res => res,
+            },
+            Pager::Stdout(stdout) => stdout.flush(),
+        }
+    }
+}
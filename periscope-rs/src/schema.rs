@@ -0,0 +1,292 @@
+use serde_json::{json, Value};
+
+/// JSON output of periscope for which a schema can be generated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum SchemaKind {
+    /// Witness as written by the 'merge' command.
+    Witness,
+
+    /// Benchmark results as written by the 'bench' command.
+    Results,
+}
+
+const DRAFT: &str = "https://json-schema.org/draft/2020-12/schema";
+
+/// JSON Schema document describing the given output.
+///
+/// The schemas are written by hand and follow the serde representation of the corresponding
+/// types, so they have to be updated together with these types.
+pub fn schema(kind: SchemaKind) -> Value {
+    match kind {
+        SchemaKind::Witness => json!({
+            "$schema": DRAFT,
+            "title": "Witness",
+            "description": "Witness of btormc, possibly consisting of several witness formats.",
+            "type": "object",
+            "properties": {
+                "formats": { "type": "array", "items": { "$ref": "#/$defs/WitnessFormat" } }
+            },
+            "required": ["formats"],
+            "$defs": witness_defs(),
+        }),
+        SchemaKind::Results => {
+            let mut defs = witness_defs();
+            defs.extend(results_defs());
+
+            json!({
+                "$schema": DRAFT,
+                "title": "BenchResults",
+                "description": "Benchmark results together with metadata about how they were produced.",
+                "type": "object",
+                "properties": {
+                    "schema_version": {
+                        "description": "Version of the format of the results file.",
+                        "const": crate::bench::RESULTS_SCHEMA_VERSION,
+                    },
+                    "metadata": reference("Metadata"),
+                    "results": {
+                        "description": "Benchmark results by name of the benchmarked BTOR2 file.",
+                        "type": "object",
+                        "additionalProperties": reference("BenchResult"),
+                    },
+                },
+                "required": ["schema_version", "metadata", "results"],
+                "$defs": defs,
+            })
+        }
+    }
+}
+
+fn object(properties: Value, required: &[&str]) -> Value {
+    json!({
+        "type": "object",
+        "properties": properties,
+        "required": required,
+    })
+}
+
+fn unsigned() -> Value {
+    json!({ "type": "integer", "minimum": 0 })
+}
+
+fn nullable(schema: Value) -> Value {
+    json!({ "anyOf": [schema, { "type": "null" }] })
+}
+
+fn reference(name: &str) -> Value {
+    json!({ "$ref": format!("#/$defs/{name}") })
+}
+
+/// Externally tagged enum variant, as serialized by serde.
+fn variant(name: &str, content: Value) -> Value {
+    let mut properties = serde_json::Map::new();
+    properties.insert(name.to_string(), content);
+
+    object(Value::Object(properties), &[name])
+}
+
+fn witness_defs() -> serde_json::Map<String, Value> {
+    let defs = json!({
+        "PropKind": { "enum": ["Bad", "Justice", "Constraint"] },
+        "Property": object(
+            json!({
+                "node": unsigned(),
+                "_kind": reference("PropKind"),
+                "name": nullable(json!({ "type": "string" })),
+                "source": { "type": "string" },
+            }),
+            &["node", "_kind", "name"],
+        ),
+        "Prop": object(
+            json!({
+                "kind": reference("PropKind"),
+                "idx": unsigned(),
+                "property": nullable(reference("Property")),
+                "block": unsigned(),
+            }),
+            &["kind", "idx", "property"],
+        ),
+        "AssignmentKind": {
+            "oneOf": [
+                variant(
+                    "BitVec",
+                    object(json!({ "value": unsigned(), "bits": unsigned() }), &["value", "bits"]),
+                ),
+                variant(
+                    "Array",
+                    object(
+                        json!({
+                            "index": unsigned(),
+                            "index_bits": unsigned(),
+                            "value": unsigned(),
+                            "bits": unsigned(),
+                        }),
+                        &["index", "value", "bits"],
+                    ),
+                ),
+            ]
+        },
+        "Assignment": object(
+            json!({
+                "kind": reference("AssignmentKind"),
+                "symbol": nullable(json!({ "type": "string" })),
+                "index": unsigned(),
+            }),
+            &["kind", "symbol"],
+        ),
+        "Transition": object(
+            json!({
+                "step": unsigned(),
+                "model": object(
+                    json!({ "assignments": { "type": "array", "items": reference("Assignment") } }),
+                    &["assignments"],
+                ),
+            }),
+            &["step", "model"],
+        ),
+        "WitnessFrame": object(
+            json!({
+                "state_part": nullable(reference("Transition")),
+                "input_part": reference("Transition"),
+            }),
+            &["state_part", "input_part"],
+        ),
+        "WitnessFormat": object(
+            json!({
+                "header": object(
+                    json!({ "props": { "type": "array", "items": reference("Prop") } }),
+                    &["props"],
+                ),
+                "frames": { "type": "array", "items": reference("WitnessFrame") },
+            }),
+            &["header", "frames"],
+        ),
+    });
+
+    match defs {
+        Value::Object(defs) => defs,
+        _ => unreachable!("definitions are always an object"),
+    }
+}
+
+fn results_defs() -> serde_json::Map<String, Value> {
+    let measurements = {
+        let number = json!({ "type": "number" });
+        object(
+            json!({
+                "command": { "type": "string" },
+                "mean": number,
+                "stddev": number,
+                "median": number,
+                "user": number,
+                "system": number,
+                "min": number,
+                "max": number,
+                "times": { "type": "array", "items": number },
+                "exit_codes": { "type": "array", "items": { "type": "integer" } },
+            }),
+            &[
+                "command",
+                "mean",
+                "stddev",
+                "median",
+                "user",
+                "system",
+                "min",
+                "max",
+                "times",
+                "exit_codes",
+            ],
+        )
+    };
+
+    let defs = json!({
+        "Metadata": object(
+            json!({
+                "periscope_version": { "type": "string" },
+                "timestamp": { "type": "string", "format": "date-time" },
+                "hostname": { "type": "string" },
+                "cpus": unsigned(),
+                "selfie_commit": { "type": "string" },
+                "rotor_args": { "type": "string" },
+                "config": object(
+                    json!({
+                        "timeout": nullable(unsigned()),
+                        "btormc-flags": nullable(json!({ "type": "string" })),
+                        "btormc-threads": nullable(unsigned()),
+                        "files": { "type": "array", "items": { "type": "string" } },
+                        "runs": {
+                            "type": "object",
+                            "additionalProperties": { "type": "string" },
+                        },
+                        "make-targets": nullable(
+                            json!({ "type": "array", "items": { "type": "string" } })
+                        ),
+                    }),
+                    &["timeout", "btormc-flags", "files", "runs", "make-targets"],
+                ),
+                "selfie_tree": object(
+                    json!({
+                        "commit": { "type": "string" },
+                        "diff_stat": { "type": "string" },
+                        "untracked": { "type": "array", "items": { "type": "string" } },
+                    }),
+                    &["commit", "diff_stat", "untracked"],
+                ),
+            }),
+            &["periscope_version", "timestamp", "config"],
+        ),
+        "Hyperfine": object(
+            json!({ "results": { "type": "array", "items": measurements } }),
+            &["results"],
+        ),
+        "BenchProp": object(
+            json!({
+                "kind": reference("PropKind"),
+                "name": nullable(json!({ "type": "string" })),
+                "node": unsigned(),
+                "idx": unsigned(),
+            }),
+            &["kind", "name", "node", "idx"],
+        ),
+        "BenchResult": {
+            "oneOf": [
+                variant(
+                    "Success",
+                    object(
+                        json!({
+                            "props": { "type": "array", "items": reference("BenchProp") },
+                            "steps": unsigned(),
+                            "hyperfine": reference("Hyperfine"),
+                            "wc_raw": unsigned(),
+                            "wc_btormc_dump": unsigned(),
+                            "wc_btormc_dump_samples": { "type": "array", "items": unsigned() },
+                            "chars_per_second": { "type": "number", "minimum": 0 },
+                            "rotor_args": { "type": "string" },
+                        }),
+                        &["props", "steps", "hyperfine", "wc_raw", "wc_btormc_dump"],
+                    ),
+                ),
+                variant(
+                    "Failed",
+                    object(
+                        json!({
+                            "output": { "type": "string" },
+                            "hyperfine": reference("Hyperfine"),
+                            "wc_raw": unsigned(),
+                            "wc_btormc_dump": unsigned(),
+                            "wc_btormc_dump_samples": { "type": "array", "items": unsigned() },
+                            "rotor_args": { "type": "string" },
+                        }),
+                        &["output", "hyperfine", "wc_raw", "wc_btormc_dump"],
+                    ),
+                ),
+            ]
+        },
+    });
+
+    match defs {
+        Value::Object(defs) => defs,
+        _ => unreachable!("definitions are always an object"),
+    }
+}